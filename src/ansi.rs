@@ -0,0 +1,98 @@
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Applies the parameters of an SGR escape sequence like `\x1b[1;36m`
+fn apply_sgr(style: Style, params: &str) -> Style {
+    let codes: Vec<u8> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let mut style = style;
+    let mut i = 0;
+    while i < codes.len() {
+        style = match codes[i] {
+            0 => Style::default(),
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            7 => style.add_modifier(Modifier::REVERSED),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            c @ 30..=37 => style.fg(Color::Indexed(c - 30)),
+            c @ 90..=97 => style.fg(Color::Indexed(c - 90 + 8)),
+            c @ 40..=47 => style.bg(Color::Indexed(c - 40)),
+            c @ 100..=107 => style.bg(Color::Indexed(c - 100 + 8)),
+            39 => style.fg(Color::Reset),
+            49 => style.bg(Color::Reset),
+            c @ (38 | 48) => {
+                // Extended colours are `5;<index>` or `2;<r>;<g>;<b>`
+                let color = match codes.get(i + 1) {
+                    Some(5) => codes.get(i + 2).map(|n| Color::Indexed(*n)),
+                    Some(2) if i + 4 < codes.len() => {
+                        Some(Color::Rgb(codes[i + 2], codes[i + 3], codes[i + 4]))
+                    }
+                    _ => None,
+                };
+                i += match codes.get(i + 1) {
+                    Some(5) => 2,
+                    Some(2) => 4,
+                    _ => 0,
+                };
+                match (color, c) {
+                    (Some(color), 38) => style.fg(color),
+                    (Some(color), _) => style.bg(color),
+                    (None, _) => style,
+                }
+            }
+            _ => style,
+        };
+        i += 1;
+    }
+    return style;
+}
+
+/// Line with the colours of the SGR escape sequences in the text, other escape
+/// sequences are dropped
+pub fn ansi_line(text: &str) -> Line<'static> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut style = Style::default();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            current.push(c);
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            continue;
+        }
+        chars.next();
+        let mut params = String::new();
+        let mut end = None;
+        for c in chars.by_ref() {
+            if c.is_ascii_alphabetic() {
+                end = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if end == Some('m') {
+            if !current.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut current), style));
+            }
+            style = apply_sgr(style, &params);
+        }
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(current, style));
+    }
+    return Line::from(spans);
+}
+
+/// Text without the escape sequences
+pub fn strip(text: &str) -> String {
+    return ansi_line(text)
+        .spans
+        .iter()
+        .map(|s| s.content.as_ref())
+        .collect();
+}
@@ -1,13 +1,15 @@
 use crate::{git, listtui::GiermError};
-use std::collections::HashMap;
+use base64::Engine;
 
 const API_URL: &str = "https://api.github.com";
+const GRAPHQL_URL: &str = "https://api.github.com/graphql";
 const PER_PAGE: i32 = 100;
 
 pub async fn fetch_user(user: &mut git::User) -> Result<(), GiermError> {
     let git_user_some = search_gituser(user, &user.git.username).await;
     if let Some(git_user) = git_user_some {
         user.git = git_user;
+        user.git.orgs = fetch_user_orgs(user).await;
         fetch_rate(user).await;
         return Ok(());
     } else {
@@ -29,7 +31,22 @@ pub async fn search_gituser(user: &git::User, username: &String) -> Option<git::
                 v["email"].to_string().replace("\"", ""),
                 v["bio"].to_string().replace("\"", ""),
             );
-            git_user.repos = fetch_repos(user, username).await;
+            git_user.is_org = v["type"].as_str() == Some("Organization");
+            git_user.followers = v["followers"].as_i64().unwrap_or(0) as i32;
+            git_user.following = v["following"].as_i64().unwrap_or(0) as i32;
+            git_user.created_at =
+                chrono::DateTime::parse_from_rfc3339(v["created_at"].as_str().unwrap_or(""))
+                    .unwrap_or_default()
+                    .into();
+            if git_user.is_org {
+                git_user.repos =
+                    fetch_org_repos(user, &git_user.username, git::RepoFilter::All).await;
+                git_user.members = fetch_org_members(user, &git_user.username).await;
+                git_user.teams = fetch_org_teams(user, &git_user.username).await;
+            } else {
+                git_user.repos = fetch_repos(user, username).await;
+                git_user.contributions = fetch_contributions(user, &git_user.username).await;
+            }
             return Some(git_user);
         }
         Err(e) => {
@@ -50,7 +67,137 @@ pub async fn fetch_rate(user: &mut git::User) {
     }
 }
 
-pub async fn fetch_repos(user: &git::User, username: &String) -> HashMap<String, git::Repo> {
+fn repo_from_json(r: &serde_json::Value) -> git::Repo {
+    let mut repo = git::Repo::new(
+        r["owner"]["login"].to_string().replace("\"", ""),
+        r["name"].to_string().replace("\"", ""),
+        r["description"].to_string().replace("\"", ""),
+        r["language"].to_string().replace("\"", ""),
+        r["created_at"].to_string().replace("\"", ""),
+        r["updated_at"].to_string().replace("\"", ""),
+    );
+    repo.archived = r["archived"].as_bool().unwrap_or(false);
+    repo.fork = r["fork"].as_bool().unwrap_or(false);
+    repo.private = r["private"].as_bool().unwrap_or(false);
+    repo.stars = r["stargazers_count"].as_i64().unwrap_or(0) as i32;
+    repo.forks = r["forks_count"].as_i64().unwrap_or(0) as i32;
+    // Only the repo endpoint has the real watchers, lists repeat the stars
+    repo.watchers = r["subscribers_count"]
+        .as_i64()
+        .or(r["watchers_count"].as_i64())
+        .unwrap_or(0) as i32;
+    repo.open_issues = r["open_issues_count"].as_i64().unwrap_or(0) as i32;
+    repo.size = r["size"].as_i64().unwrap_or(0);
+    repo.license = r["license"]["spdx_id"].as_str().unwrap_or("").to_string();
+    if let serde_json::Value::Array(topics) = &r["topics"] {
+        repo.topics = topics
+            .iter()
+            .filter_map(|t| t.as_str().map(|t| t.to_string()))
+            .collect();
+    }
+    repo.default_branch = r["default_branch"].as_str().unwrap_or("").to_string();
+    repo.visibility = match r["visibility"].as_str() {
+        Some(v) => v.to_string(),
+        None if repo.private => "private".to_string(),
+        None => "public".to_string(),
+    };
+    return repo;
+}
+
+fn repos_from_json(v: &serde_json::Value) -> git::Repos {
+    let mut all_repos = git::Repos::new();
+    if let serde_json::Value::Array(repos) = v {
+        for (_i, r) in repos.iter().enumerate() {
+            let repo: git::Repo = repo_from_json(r);
+            all_repos.insert(repo);
+        }
+    }
+    return all_repos;
+}
+
+/// Fetches a list of objects and collects the given string field of each
+async fn fetch_names(user: &git::User, url: &String, key: &str) -> Vec<String> {
+    match fetch_data(url, &user).await {
+        Ok((serde_json::Value::Array(items), _)) => {
+            return items
+                .iter()
+                .map(|i| i[key].to_string().replace("\"", ""))
+                .collect();
+        }
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
+/// Contributions per day of the last year from the contribution calendar
+pub async fn fetch_contributions(
+    user: &git::User,
+    username: &String,
+) -> Vec<(chrono::NaiveDate, i32)> {
+    let query = "query($login: String!) { user(login: $login) { contributionsCollection { \
+        contributionCalendar { weeks { contributionDays { date contributionCount } } } } } }";
+    match post_graphql(user, query, serde_json::json!({ "login": username })).await {
+        Ok(v) => {
+            let mut days = Vec::new();
+            let calendar = &v["data"]["user"]["contributionsCollection"]["contributionCalendar"];
+            if let serde_json::Value::Array(weeks) = &calendar["weeks"] {
+                for day in weeks
+                    .iter()
+                    .filter_map(|w| w["contributionDays"].as_array())
+                    .flatten()
+                {
+                    let date = day["date"].as_str().unwrap_or("");
+                    if let Ok(date) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                        days.push((date, day["contributionCount"].as_i64().unwrap_or(0) as i32));
+                    }
+                }
+            }
+            return days;
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
+pub async fn fetch_user_orgs(user: &git::User) -> Vec<String> {
+    return fetch_names(user, &format!("{}/user/orgs", API_URL), "login").await;
+}
+
+pub async fn fetch_org_members(user: &git::User, org: &String) -> Vec<String> {
+    return fetch_names(user, &format!("{}/orgs/{}/members", API_URL, org), "login").await;
+}
+
+pub async fn fetch_org_teams(user: &git::User, org: &String) -> Vec<String> {
+    return fetch_names(user, &format!("{}/orgs/{}/teams", API_URL, org), "name").await;
+}
+
+pub async fn fetch_org_repos(
+    user: &git::User,
+    org: &String,
+    filter: git::RepoFilter,
+) -> git::Repos {
+    let url = format!("{}/orgs/{}/repos?type={}", API_URL, org, filter.api_type());
+    match fetch_data(&url, &user).await {
+        Ok((v, _)) => {
+            let mut repos = repos_from_json(&v);
+            if filter == git::RepoFilter::Archived {
+                repos.retain(|r| r.archived);
+            }
+            return repos;
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return git::Repos::new();
+        }
+    }
+}
+
+pub async fn fetch_repos(user: &git::User, username: &String) -> git::Repos {
     let url = match username.to_lowercase() == user.git.username.to_lowercase() {
         true => format!("{}/user/repos", API_URL),
         false => format!("{}/users/{}/repos", API_URL, username),
@@ -58,47 +205,240 @@ pub async fn fetch_repos(user: &git::User, username: &String) -> HashMap<String,
     let res = fetch_data(&url, &user).await;
     match res {
         Ok((v, _)) => {
-            let mut all_repos: HashMap<String, git::Repo> = HashMap::new();
-            if let serde_json::Value::Array(repos) = &v {
-                for (_i, r) in repos.iter().enumerate() {
-                    let repo: git::Repo = git::Repo::new(
-                        r["owner"]["login"].to_string().replace("\"", ""),
-                        r["name"].to_string().replace("\"", ""),
-                        r["description"].to_string().replace("\"", ""),
-                        r["language"].to_string().replace("\"", ""),
-                        r["created_at"].to_string().replace("\"", ""),
-                        r["updated_at"].to_string().replace("\"", ""),
-                    );
-                    all_repos.insert(repo.name.clone(), repo);
-                }
-            }
-            return all_repos;
+            return repos_from_json(&v);
         }
         Err(e) => {
             println!("Error: {:?}", e);
-            return HashMap::new();
+            return git::Repos::new();
         }
     }
 }
 
+/// The repo, or None when it does not exist
 pub async fn fetch_repo(
     user: &git::User,
     username: &String,
     repo_name: &String,
-) -> Option<git::Repo> {
+) -> Result<Option<git::Repo>, String> {
     let url = format!("{}/repos/{}/{}", API_URL, username, repo_name);
+    let (r, s) = fetch_data(&url, &user).await.map_err(|e| e.to_string())?;
+    return match s {
+        200..=299 => Ok(Some(repo_from_json(&r))),
+        404 => Ok(None),
+        _ => Err(match r["message"].as_str() {
+            Some(message) => format!("{} {}", s, message),
+            None => s.to_string(),
+        }),
+    };
+}
+
+fn commit_from_json(c: &serde_json::Value) -> git::Commit {
+    let mut commit: git::Commit = git::Commit::new(
+        c["commit"]["message"].to_string().replace("\"", ""),
+        c["sha"].to_string().replace("\"", ""),
+        c["committer"]["login"].to_string().replace("\"", ""),
+        c["commit"]["author"]["date"].to_string().replace("\"", ""),
+    );
+    commit.author = c["author"]["login"]
+        .as_str()
+        .or(c["commit"]["author"]["name"].as_str())
+        .unwrap_or_default()
+        .to_string();
+    if let serde_json::Value::Array(parents) = &c["parents"] {
+        commit.parents = parents
+            .iter()
+            .filter_map(|p| p["sha"].as_str().map(|s| s.to_string()))
+            .collect();
+    }
+    return commit;
+}
+
+fn commits_from_json(v: &serde_json::Value) -> Vec<git::Commit> {
+    let mut commits: Vec<git::Commit> = Vec::new();
+    if let serde_json::Value::Array(items) = v {
+        for c in items.iter() {
+            commits.push(commit_from_json(c));
+        }
+    }
+    return commits;
+}
+
+fn files_from_json(v: &serde_json::Value) -> Vec<git::File> {
+    let mut files: Vec<git::File> = Vec::new();
+    if let serde_json::Value::Array(items) = v {
+        for f in items.iter() {
+            let file: git::File = git::File::new(
+                f["filename"].to_string().replace("\"", ""),
+                f["sha"].to_string().replace("\"", ""),
+                f["additions"].as_i64().unwrap_or(0) as i32,
+                f["deletions"].as_i64().unwrap_or(0) as i32,
+                f["patch"].to_string(),
+            );
+            files.push(file);
+        }
+    }
+    return files;
+}
+
+pub async fn fetch_repo_commits(user: &git::User, repo: &git::Repo) -> Vec<git::Commit> {
+    let url = match reqwest::Url::parse_with_params(
+        &format!("{}/repos/{}/{}/commits", API_URL, repo.user, repo.name),
+        &repo.commit_filter.params,
+    ) {
+        Ok(url) => url.to_string(),
+        Err(_) => return Vec::new(),
+    };
     let res = fetch_data(&url, &user).await;
     match res {
-        Ok((r, _)) => {
-            let repo: git::Repo = git::Repo::new(
-                r["owner"]["login"].to_string().replace("\"", ""),
-                r["name"].to_string().replace("\"", ""),
-                r["description"].to_string().replace("\"", ""),
-                r["language"].to_string().replace("\"", ""),
-                r["created_at"].to_string().replace("\"", ""),
-                r["updated_at"].to_string().replace("\"", ""),
-            );
-            return Some(repo);
+        Ok((v, _)) => {
+            return commits_from_json(&v);
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
+/// Status and conclusion of a check run for the state of a commit status
+fn check_state(state: &str) -> (String, String) {
+    let (status, conclusion) = match state {
+        "SUCCESS" => ("completed", "success"),
+        "FAILURE" | "ERROR" => ("completed", "failure"),
+        _ => ("in_progress", ""),
+    };
+    return (status.to_string(), conclusion.to_string());
+}
+
+/// Combined status and check state of each commit, None for commits without checks.
+/// A query for each 100 commits, commits of failed queries are left out.
+pub async fn fetch_commit_states(
+    user: &git::User,
+    repo: &git::Repo,
+    shas: &[String],
+) -> Vec<(String, Option<git::Check>)> {
+    let mut states: Vec<(String, Option<git::Check>)> = Vec::new();
+    for chunk in shas.chunks(100) {
+        let objects: Vec<String> = chunk
+            .iter()
+            .enumerate()
+            .map(|(i, sha)| {
+                format!(
+                    "c{}: object(oid: \"{}\") {{ ... on Commit {{ statusCheckRollup {{ state }} }} }}",
+                    i, sha
+                )
+            })
+            .collect();
+        let query = format!(
+            "query($owner: String!, $name: String!) {{ repository(owner: $owner, name: $name) {{ {} }} }}",
+            objects.join(" ")
+        );
+        let variables = serde_json::json!({ "owner": repo.user, "name": repo.name });
+        let v = match post_graphql(user, &query, variables).await {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        for (i, sha) in chunk.iter().enumerate() {
+            let state = &v["data"]["repository"][format!("c{}", i)]["statusCheckRollup"]["state"];
+            let check = state.as_str().map(|state| {
+                let (status, conclusion) = check_state(state);
+                git::Check {
+                    name: String::new(),
+                    status,
+                    conclusion,
+                    url: String::new(),
+                }
+            });
+            states.push((sha.clone(), check));
+        }
+    }
+    return states;
+}
+
+/// Statuses and check runs of the commit
+async fn fetch_commit_checks(
+    user: &git::User,
+    owner: &String,
+    name: &String,
+    sha: &String,
+) -> Vec<git::Check> {
+    let query = "query($owner: String!, $name: String!, $sha: GitObjectID!) { \
+        repository(owner: $owner, name: $name) { object(oid: $sha) { ... on Commit { \
+        statusCheckRollup { contexts(first: 100) { nodes { __typename \
+        ... on CheckRun { name status conclusion detailsUrl } \
+        ... on StatusContext { context state targetUrl } } } } } } } }";
+    let variables = serde_json::json!({ "owner": owner, "name": name, "sha": sha });
+    let v = match post_graphql(user, query, variables).await {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+    let nodes = match v["data"]["repository"]["object"]["statusCheckRollup"]["contexts"]["nodes"]
+        .as_array()
+    {
+        Some(nodes) => nodes,
+        None => return Vec::new(),
+    };
+    return nodes
+        .iter()
+        .map(|n| match n["__typename"].as_str() {
+            Some("CheckRun") => git::Check {
+                name: str_from_json(&n["name"]),
+                status: str_from_json(&n["status"]).to_lowercase(),
+                conclusion: str_from_json(&n["conclusion"]).to_lowercase(),
+                url: str_from_json(&n["detailsUrl"]),
+            },
+            _ => {
+                let (status, conclusion) = check_state(n["state"].as_str().unwrap_or(""));
+                git::Check {
+                    name: str_from_json(&n["context"]),
+                    status,
+                    conclusion,
+                    url: str_from_json(&n["targetUrl"]),
+                }
+            }
+        })
+        .collect();
+}
+
+/// Names of the branches of the repo and the shas of their heads
+pub async fn fetch_repo_branches(user: &git::User, repo: &git::Repo) -> Vec<(String, String)> {
+    return fetch_refs(user, repo, "branches").await;
+}
+
+pub async fn fetch_repo_tags(user: &git::User, repo: &git::Repo) -> Vec<(String, String)> {
+    return fetch_refs(user, repo, "tags").await;
+}
+
+/// Changes from base to head, either commit shas, branches or tags
+pub async fn fetch_comparison(
+    user: &git::User,
+    repo: &git::Repo,
+    base: &String,
+    head: &String,
+) -> Option<git::Comparison> {
+    let url = format!(
+        "{}/repos/{}/{}/compare/{}...{}",
+        API_URL, repo.user, repo.name, base, head
+    );
+    match fetch_data(&url, &user).await {
+        Ok((v, s)) => {
+            if s != 200 {
+                return None;
+            }
+            let files = files_from_json(&v["files"]);
+            let additions: i32 = files.iter().map(|f| f.additions).sum();
+            let deletions: i32 = files.iter().map(|f| f.deletions).sum();
+            let mut info = git::CommitInfo::new(additions + deletions, additions, deletions);
+            info.files = files;
+            return Some(git::Comparison {
+                base: base.clone(),
+                head: head.clone(),
+                status: v["status"].as_str().unwrap_or("").to_string(),
+                ahead_by: v["ahead_by"].as_i64().unwrap_or(0) as i32,
+                behind_by: v["behind_by"].as_i64().unwrap_or(0) as i32,
+                commits: commits_from_json(&v["commits"]),
+                info,
+            });
         }
         Err(e) => {
             println!("Error: {:?}", e);
@@ -107,25 +447,544 @@ pub async fn fetch_repo(
     }
 }
 
-pub async fn fetch_repo_commits(user: &git::User, repo: &git::Repo) -> Vec<git::Commit> {
-    let url = format!("{}/repos/{}/{}/commits", API_URL, repo.user, repo.name);
-    let res = fetch_data(&url, &user).await;
-    match res {
+fn time_from_json(v: &serde_json::Value) -> Option<chrono::DateTime<chrono::Utc>> {
+    return git::parse_time(v.as_str()?);
+}
+
+fn str_from_json(v: &serde_json::Value) -> String {
+    return v.as_str().unwrap_or("").to_string();
+}
+
+/// Latest workflow runs of the repo
+pub async fn fetch_workflow_runs(user: &git::User, repo: &git::Repo) -> Vec<git::WorkflowRun> {
+    let url = format!("{}/repos/{}/{}/actions/runs", API_URL, repo.user, repo.name);
+    match fetch_data_pages(&url, &user, Some(1)).await {
         Ok((v, _)) => {
-            let mut repo_commits: Vec<git::Commit> = Vec::new();
-            if let serde_json::Value::Array(commits) = v {
-                for (_i, c) in commits.iter().enumerate() {
-                    let commit: git::Commit = git::Commit::new(
-                        c["commit"]["message"].to_string().replace("\"", ""),
-                        c["sha"].to_string().replace("\"", ""),
-                        c["committer"]["login"].to_string().replace("\"", ""),
-                        c["commit"]["author"]["date"].to_string().replace("\"", ""),
-                    );
-                    repo_commits.push(commit);
-                }
+            let runs = match v["workflow_runs"].as_array() {
+                Some(runs) => runs,
+                None => return Vec::new(),
+            };
+            return runs
+                .iter()
+                .map(|r| git::WorkflowRun {
+                    id: r["id"].as_u64().unwrap_or(0),
+                    workflow_id: r["workflow_id"].as_u64().unwrap_or(0),
+                    name: str_from_json(&r["name"]),
+                    title: str_from_json(&r["display_title"]),
+                    number: r["run_number"].as_i64().unwrap_or(0),
+                    status: str_from_json(&r["status"]),
+                    conclusion: str_from_json(&r["conclusion"]),
+                    branch: str_from_json(&r["head_branch"]),
+                    event: str_from_json(&r["event"]),
+                    started_at: time_from_json(&r["run_started_at"]),
+                    updated_at: time_from_json(&r["updated_at"]),
+                    url: str_from_json(&r["html_url"]),
+                    jobs: Vec::new(),
+                })
+                .collect();
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
+pub async fn fetch_run_jobs(user: &git::User, repo: &git::Repo, run_id: u64) -> Vec<git::Job> {
+    let url = format!(
+        "{}/repos/{}/{}/actions/runs/{}/jobs",
+        API_URL, repo.user, repo.name, run_id
+    );
+    match fetch_data(&url, &user).await {
+        Ok((v, _)) => {
+            let jobs = match v["jobs"].as_array() {
+                Some(jobs) => jobs,
+                None => return Vec::new(),
+            };
+            return jobs
+                .iter()
+                .map(|j| git::Job {
+                    id: j["id"].as_u64().unwrap_or(0),
+                    name: str_from_json(&j["name"]),
+                    status: str_from_json(&j["status"]),
+                    conclusion: str_from_json(&j["conclusion"]),
+                    started_at: time_from_json(&j["started_at"]),
+                    completed_at: time_from_json(&j["completed_at"]),
+                    steps: j["steps"]
+                        .as_array()
+                        .map(|steps| {
+                            steps
+                                .iter()
+                                .map(|s| git::Step {
+                                    number: s["number"].as_i64().unwrap_or(0),
+                                    name: str_from_json(&s["name"]),
+                                    status: str_from_json(&s["status"]),
+                                    conclusion: str_from_json(&s["conclusion"]),
+                                    started_at: time_from_json(&s["started_at"]),
+                                    completed_at: time_from_json(&s["completed_at"]),
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+                .collect();
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
+/// Plain text log of the job
+pub async fn fetch_job_log(user: &git::User, repo: &git::Repo, job_id: u64) -> Option<String> {
+    let url = format!(
+        "{}/repos/{}/{}/actions/jobs/{}/logs",
+        API_URL, repo.user, repo.name, job_id
+    );
+    let res = reqwest::Client::new()
+        .get(url)
+        .headers(request_headers(user))
+        .send()
+        .await
+        .ok()?;
+    if !res.status().is_success() {
+        return None;
+    }
+    return res.text().await.ok();
+}
+
+pub async fn rerun_workflow_run(
+    user: &git::User,
+    repo: &git::Repo,
+    run_id: u64,
+) -> Result<(), String> {
+    let url = format!(
+        "{}/repos/{}/{}/actions/runs/{}/rerun",
+        API_URL, repo.user, repo.name, run_id
+    );
+    return send_data(reqwest::Method::POST, &url, user, serde_json::Value::Null).await;
+}
+
+pub async fn cancel_workflow_run(
+    user: &git::User,
+    repo: &git::Repo,
+    run_id: u64,
+) -> Result<(), String> {
+    let url = format!(
+        "{}/repos/{}/{}/actions/runs/{}/cancel",
+        API_URL, repo.user, repo.name, run_id
+    );
+    return send_data(reqwest::Method::POST, &url, user, serde_json::Value::Null).await;
+}
+
+/// Starts a workflow that has a `workflow_dispatch` trigger on the ref
+pub async fn dispatch_workflow(
+    user: &git::User,
+    repo: &git::Repo,
+    workflow_id: u64,
+    git_ref: &String,
+    inputs: &Vec<(String, String)>,
+) -> Result<(), String> {
+    let url = format!(
+        "{}/repos/{}/{}/actions/workflows/{}/dispatches",
+        API_URL, repo.user, repo.name, workflow_id
+    );
+    let inputs: serde_json::Map<String, serde_json::Value> = inputs
+        .iter()
+        .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+        .collect();
+    let body = serde_json::json!({ "ref": git_ref, "inputs": inputs });
+    return send_data(reqwest::Method::POST, &url, user, body).await;
+}
+
+/// What happened in the event, from its type and payload
+fn event_summary(kind: &str, p: &serde_json::Value) -> String {
+    let action = p["action"].as_str().unwrap_or("");
+    let git_ref = str_from_json(&p["ref"]).replace("refs/heads/", "");
+    return match kind {
+        "PushEvent" => match p["size"].as_i64() {
+            Some(1) => format!("pushed 1 commit to {}", git_ref),
+            Some(n) => format!("pushed {} commits to {}", n, git_ref),
+            None => format!("pushed to {}", git_ref),
+        },
+        "PullRequestEvent" => format!(
+            "{} pull request #{} {}",
+            action,
+            p["number"].as_i64().unwrap_or(0),
+            str_from_json(&p["pull_request"]["title"])
+        ),
+        "PullRequestReviewEvent" => format!(
+            "reviewed pull request #{}",
+            p["pull_request"]["number"].as_i64().unwrap_or(0)
+        ),
+        "PullRequestReviewCommentEvent" => format!(
+            "commented on pull request #{}",
+            p["pull_request"]["number"].as_i64().unwrap_or(0)
+        ),
+        "IssuesEvent" => format!(
+            "{} issue #{} {}",
+            action,
+            p["issue"]["number"].as_i64().unwrap_or(0),
+            str_from_json(&p["issue"]["title"])
+        ),
+        "IssueCommentEvent" => format!(
+            "commented on #{} {}",
+            p["issue"]["number"].as_i64().unwrap_or(0),
+            str_from_json(&p["issue"]["title"])
+        ),
+        "WatchEvent" => "starred".to_string(),
+        "ForkEvent" => "forked".to_string(),
+        "ReleaseEvent" => format!(
+            "{} release {}",
+            action,
+            str_from_json(&p["release"]["tag_name"])
+        ),
+        "CreateEvent" | "DeleteEvent" => format!(
+            "{} {} {}",
+            kind.trim_end_matches("Event").to_lowercase() + "d",
+            str_from_json(&p["ref_type"]),
+            git_ref
+        ),
+        "PublicEvent" => "made public".to_string(),
+        "MemberEvent" => format!("{} member {}", action, str_from_json(&p["member"]["login"])),
+        _ => kind.trim_end_matches("Event").to_string(),
+    };
+}
+
+/// Events received by the user, or the public events of the user
+pub async fn fetch_events(user: &git::User, login: &String, received: bool) -> Vec<git::Event> {
+    let url = match received {
+        true => format!("{}/users/{}/received_events", API_URL, login),
+        false => format!("{}/users/{}/events/public", API_URL, login),
+    };
+    let items = match fetch_data_pages(&url, &user, Some(1)).await {
+        Ok((serde_json::Value::Array(items), _)) => items,
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    };
+    return items
+        .iter()
+        .map(|e| {
+            let kind = str_from_json(&e["type"]);
+            let payload = &e["payload"];
+            let pull = &payload["pull_request"];
+            git::Event {
+                summary: event_summary(&kind, payload),
+                actor: str_from_json(&e["actor"]["login"]),
+                repo: match kind.as_str() {
+                    "ForkEvent" => str_from_json(&payload["forkee"]["full_name"]),
+                    _ => str_from_json(&e["repo"]["name"]),
+                },
+                created_at: time_from_json(&e["created_at"]),
+                sha: str_from_json(&payload["head"]),
+                compare: match (pull["base"]["sha"].as_str(), pull["head"]["sha"].as_str()) {
+                    (Some(base), Some(head)) => Some((base.to_string(), head.to_string())),
+                    _ => None,
+                },
             }
-            return repo_commits;
+        })
+        .collect();
+}
+
+/// Latest releases of the repo, newest first
+pub async fn fetch_releases(user: &git::User, repo: &git::Repo) -> Vec<git::Release> {
+    let url = format!("{}/repos/{}/{}/releases", API_URL, repo.user, repo.name);
+    let items = match fetch_data_pages(&url, &user, Some(1)).await {
+        Ok((serde_json::Value::Array(items), _)) => items,
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    };
+    return items
+        .iter()
+        .map(|r| git::Release {
+            tag: str_from_json(&r["tag_name"]),
+            name: str_from_json(&r["name"]),
+            notes: str_from_json(&r["body"]),
+            author: str_from_json(&r["author"]["login"]),
+            draft: r["draft"].as_bool().unwrap_or(false),
+            prerelease: r["prerelease"].as_bool().unwrap_or(false),
+            published_at: time_from_json(&r["published_at"]),
+            url: str_from_json(&r["html_url"]),
+            assets: r["assets"]
+                .as_array()
+                .map(|assets| {
+                    assets
+                        .iter()
+                        .map(|a| git::Asset {
+                            name: str_from_json(&a["name"]),
+                            size: a["size"].as_u64().unwrap_or(0),
+                            url: str_from_json(&a["url"]),
+                            download_count: a["download_count"].as_i64().unwrap_or(0),
+                            digest: str_from_json(&a["digest"]),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect();
+}
+
+/// Downloads the asset next to the path as `<path>.part`, calling `progress` with the
+/// bytes written so far, and moves it to the path once its sha256 matches the digest of
+/// the asset. Gives the sha256 in hex, the part file is removed on errors.
+pub async fn download_asset(
+    user: &git::User,
+    asset: &git::Asset,
+    path: &std::path::Path,
+    progress: impl FnMut(u64),
+) -> Result<String, String> {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = std::path::PathBuf::from(part);
+    let result = match write_asset(user, asset, &part, progress).await {
+        Ok(checksum) => match asset.digest.strip_prefix("sha256:") {
+            Some(digest) if digest != checksum => Err(format!(
+                "sha256 {} does not match the digest {} on GitHub",
+                checksum, digest
+            )),
+            _ => std::fs::rename(&part, path)
+                .map(|_| checksum)
+                .map_err(|e| e.to_string()),
+        },
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        let _ = std::fs::remove_file(&part);
+    }
+    return result;
+}
+
+async fn write_asset(
+    user: &git::User,
+    asset: &git::Asset,
+    path: &std::path::Path,
+    mut progress: impl FnMut(u64),
+) -> Result<String, String> {
+    use sha2::Digest;
+    use std::io::Write;
+    let mut headers = request_headers(user);
+    headers.insert(
+        "Accept",
+        reqwest::header::HeaderValue::from_static("application/octet-stream"),
+    );
+    let mut res = reqwest::Client::new()
+        .get(&asset.url)
+        .headers(headers)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(res.status().to_string());
+    }
+    let mut file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut hasher = sha2::Sha256::new();
+    let mut written: u64 = 0;
+    while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+        file.write_all(&chunk).map_err(|e| e.to_string())?;
+        hasher.update(&chunk);
+        written += chunk.len() as u64;
+        progress(written);
+    }
+    return Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect());
+}
+
+/// Unread notifications of the user
+pub async fn fetch_notifications(user: &git::User) -> Vec<git::Notification> {
+    let url = format!("{}/notifications", API_URL);
+    let items = match fetch_data_pages(&url, &user, Some(5)).await {
+        Ok((serde_json::Value::Array(items), _)) => items,
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    };
+    return items
+        .iter()
+        .map(|n| git::Notification {
+            id: str_from_json(&n["id"]),
+            repo: str_from_json(&n["repository"]["full_name"]),
+            reason: str_from_json(&n["reason"]),
+            kind: str_from_json(&n["subject"]["type"]),
+            title: str_from_json(&n["subject"]["title"]),
+            url: str_from_json(&n["subject"]["url"]),
+            unread: n["unread"].as_bool().unwrap_or(false),
+            updated_at: time_from_json(&n["updated_at"]),
+        })
+        .collect();
+}
+
+pub async fn mark_notification_read(user: &git::User, id: &String) -> Result<(), String> {
+    let url = format!("{}/notifications/threads/{}", API_URL, id);
+    return send_data(reqwest::Method::PATCH, &url, user, serde_json::Value::Null).await;
+}
+
+/// Marks the notification done, which removes it from the inbox
+pub async fn mark_notification_done(user: &git::User, id: &String) -> Result<(), String> {
+    let url = format!("{}/notifications/threads/{}", API_URL, id);
+    return send_data(reqwest::Method::DELETE, &url, user, serde_json::Value::Null).await;
+}
+
+pub async fn unsubscribe_notification(user: &git::User, id: &String) -> Result<(), String> {
+    let url = format!("{}/notifications/threads/{}/subscription", API_URL, id);
+    return send_data(reqwest::Method::DELETE, &url, user, serde_json::Value::Null).await;
+}
+
+/// Shas of the base and head of the pull request at the API url
+pub async fn fetch_pull_refs(user: &git::User, url: &String) -> Option<(String, String)> {
+    let (v, _) = fetch_data(url, user).await.ok()?;
+    let base = v["base"]["sha"].as_str()?;
+    let head = v["head"]["sha"].as_str()?;
+    return Some((base.to_string(), head.to_string()));
+}
+
+/// Commits until the sha that changed the file
+pub async fn fetch_file_history(
+    user: &git::User,
+    repo: &git::Repo,
+    sha: &String,
+    path: &String,
+) -> Vec<git::Commit> {
+    let url = match reqwest::Url::parse_with_params(
+        &format!("{}/repos/{}/{}/commits", API_URL, repo.user, repo.name),
+        &[("sha", sha), ("path", path)],
+    ) {
+        Ok(url) => url.to_string(),
+        Err(_) => return Vec::new(),
+    };
+    match fetch_data_pages(&url, &user, Some(3)).await {
+        Ok((v, _)) => return commits_from_json(&v),
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
+/// Text of the file at the sha
+pub async fn fetch_file_content(
+    user: &git::User,
+    repo: &git::Repo,
+    sha: &String,
+    path: &String,
+) -> Option<String> {
+    let url = format!(
+        "{}/repos/{}/{}/contents/{}?ref={}",
+        API_URL, repo.user, repo.name, path, sha
+    );
+    match fetch_data(&url, &user).await {
+        Ok((v, _)) => {
+            let content: String = v["content"].as_str()?.split_whitespace().collect();
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(content)
+                .ok()?;
+            return Some(String::from_utf8_lossy(&bytes).to_string());
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return None;
+        }
+    }
+}
+
+/// Blame of the file at the sha, from the GraphQL API since REST has none
+pub async fn fetch_blame(
+    user: &git::User,
+    repo: &git::Repo,
+    sha: &String,
+    path: &String,
+) -> Option<git::Blame> {
+    let query = "query($owner: String!, $name: String!, $sha: String!, $path: String!) { \
+        repository(owner: $owner, name: $name) { object(expression: $sha) { ... on Commit { \
+        blame(path: $path) { ranges { startingLine endingLine commit { oid messageHeadline \
+        committedDate author { name user { login } } } } } } } } }";
+    let variables = serde_json::json!({
+        "owner": repo.user, "name": repo.name, "sha": sha, "path": path
+    });
+    let v = match post_graphql(user, query, variables).await {
+        Ok(v) => v,
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return None;
+        }
+    };
+    let ranges = v["data"]["repository"]["object"]["blame"]["ranges"].as_array()?;
+    let content = fetch_file_content(user, repo, sha, path).await?;
+    return Some(git::Blame {
+        path: path.clone(),
+        lines: content.lines().map(|l| l.to_string()).collect(),
+        ranges: ranges
+            .iter()
+            .map(|r| {
+                let commit = &r["commit"];
+                let author = match commit["author"]["user"]["login"].as_str() {
+                    Some(login) => login,
+                    None => commit["author"]["name"].as_str().unwrap_or(""),
+                };
+                git::BlameRange {
+                    start: r["startingLine"].as_u64().unwrap_or(0) as usize,
+                    end: r["endingLine"].as_u64().unwrap_or(0) as usize,
+                    sha: commit["oid"].as_str().unwrap_or("").to_string(),
+                    author: author.to_string(),
+                    date: chrono::DateTime::parse_from_rfc3339(
+                        commit["committedDate"].as_str().unwrap_or(""),
+                    )
+                    .unwrap_or_default()
+                    .into(),
+                    message: commit["messageHeadline"].as_str().unwrap_or("").to_string(),
+                }
+            })
+            .collect(),
+    });
+}
+
+/// Names of the branches or tags of the repo and the shas they point to
+async fn fetch_refs(user: &git::User, repo: &git::Repo, kind: &str) -> Vec<(String, String)> {
+    let url = format!("{}/repos/{}/{}/{}", API_URL, repo.user, repo.name, kind);
+    match fetch_data(&url, &user).await {
+        Ok((serde_json::Value::Array(branches), _)) => {
+            return branches
+                .iter()
+                .map(|b| {
+                    (
+                        b["name"].as_str().unwrap_or("").to_string(),
+                        b["commit"]["sha"].as_str().unwrap_or("").to_string(),
+                    )
+                })
+                .collect();
         }
+        Ok(_) => return Vec::new(),
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
+/// Bytes of code per language of the repo, largest first
+pub async fn fetch_repo_languages(user: &git::User, repo: &git::Repo) -> Vec<(String, i64)> {
+    let url = format!("{}/repos/{}/{}/languages", API_URL, repo.user, repo.name);
+    match fetch_data(&url, &user).await {
+        Ok((serde_json::Value::Object(languages), _)) => {
+            let mut languages: Vec<(String, i64)> = languages
+                .iter()
+                .map(|(l, b)| (l.clone(), b.as_i64().unwrap_or(0)))
+                .collect();
+            languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            return languages;
+        }
+        Ok(_) => return Vec::new(),
         Err(e) => {
             println!("Error: {:?}", e);
             return Vec::new();
@@ -151,19 +1010,9 @@ pub async fn fetch_commit_info(
                 let additions = info["stats"]["additions"].as_i64().unwrap_or(0) as i32;
                 let deletions = info["stats"]["deletions"].as_i64().unwrap_or(0) as i32;
                 let mut commit_info = git::CommitInfo::new(total, additions, deletions);
-
-                if let serde_json::Value::Array(files) = &info["files"] {
-                    for (_i, f) in files.iter().enumerate() {
-                        let file: git::File = git::File::new(
-                            f["filename"].to_string().replace("\"", ""),
-                            f["sha"].to_string().replace("\"", ""),
-                            f["additions"].as_i64().unwrap_or(0) as i32,
-                            f["deletions"].as_i64().unwrap_or(0) as i32,
-                            f["patch"].to_string(),
-                        );
-                        commit_info.files.push(file);
-                    }
-                }
+                commit_info.files = files_from_json(&info["files"]);
+                commit_info.checks =
+                    fetch_commit_checks(user, &username, &repo_name, &commit_sha).await;
                 // println!("Info: {:?}", commit_info);
                 return commit_info;
             }
@@ -176,6 +1025,105 @@ pub async fn fetch_commit_info(
     }
 }
 
+fn search_result_from_json(kind: git::SearchKind, i: &serde_json::Value) -> git::SearchResult {
+    let repository = match kind {
+        git::SearchKind::Repositories => i,
+        _ => &i["repository"],
+    };
+    let owner = repository["owner"]["login"].to_string().replace("\"", "");
+    let repo = repository["name"].to_string().replace("\"", "");
+    let full_name = format!("{}/{}", owner, repo);
+    match kind {
+        git::SearchKind::Repositories => {
+            let mut result = git::SearchResult::new(kind, owner, repo, full_name);
+            result.detail = format!(
+                "★ {} {}",
+                i["stargazers_count"].as_i64().unwrap_or(0),
+                i["description"].to_string().replace("\"", "")
+            );
+            return result;
+        }
+        git::SearchKind::Code => {
+            let mut result = git::SearchResult::new(kind, owner, repo, full_name);
+            result.detail = i["path"].to_string().replace("\"", "");
+            return result;
+        }
+        git::SearchKind::Issues => {
+            // Issues only link their repo as an api url ending in owner/repo
+            let repo_url = i["repository_url"].to_string().replace("\"", "");
+            let mut parts = repo_url.rsplit('/');
+            let repo = parts.next().unwrap_or_default().to_string();
+            let owner = parts.next().unwrap_or_default().to_string();
+            let is_pr = !i["pull_request"].is_null();
+            let mut result = git::SearchResult::new(
+                kind,
+                owner.clone(),
+                repo.clone(),
+                format!(
+                    "{}/{}{}{}",
+                    owner,
+                    repo,
+                    if is_pr { "!" } else { "#" },
+                    i["number"].as_i64().unwrap_or(0)
+                ),
+            );
+            result.detail = format!(
+                "[{}] {}",
+                i["state"].to_string().replace("\"", ""),
+                i["title"].to_string().replace("\"", "")
+            );
+            return result;
+        }
+        git::SearchKind::Commits => {
+            let mut result = git::SearchResult::new(kind, owner, repo, full_name);
+            result.sha = i["sha"].to_string().replace("\"", "");
+            let message = i["commit"]["message"].to_string().replace("\"", "");
+            result.detail = format!(
+                "{} {}",
+                result.sha.chars().take(8).collect::<String>(),
+                message.split("\\n").next().unwrap_or_default()
+            );
+            return result;
+        }
+        git::SearchKind::Users => {
+            let login = i["login"].to_string().replace("\"", "");
+            let mut result = git::SearchResult::new(kind, login.clone(), "".to_string(), login);
+            result.detail = i["type"].to_string().replace("\"", "");
+            return result;
+        }
+    }
+}
+
+/// Searches with GitHub's search syntax, e.g. `language:rust stars:>100`
+pub async fn search(
+    user: &git::User,
+    kind: git::SearchKind,
+    query: &String,
+) -> Vec<git::SearchResult> {
+    let url = match reqwest::Url::parse_with_params(
+        &format!("{}/search/{}", API_URL, kind.to_string()),
+        &[("q", query)],
+    ) {
+        Ok(url) => url.to_string(),
+        Err(_) => return Vec::new(),
+    };
+    match fetch_data_pages(&url, &user, Some(1)).await {
+        Ok((v, _)) => {
+            if let serde_json::Value::Array(items) = &v["items"] {
+                return items
+                    .iter()
+                    .map(|i| search_result_from_json(kind, i))
+                    .collect();
+            }
+            return Vec::new();
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+            return Vec::new();
+        }
+    }
+}
+
 fn extract_next_url(link_header: &reqwest::header::HeaderValue) -> Option<String> {
     let link_str = link_header.to_str().ok()?;
     let next_pattern = r#"<([^>]+)>; rel="next""#;
@@ -187,9 +1135,77 @@ fn extract_next_url(link_header: &reqwest::header::HeaderValue) -> Option<String
     }
 }
 
+fn request_headers(user: &git::User) -> reqwest::header::HeaderMap {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        "User-Agent",
+        reqwest::header::HeaderValue::from_static("gierm"),
+    );
+    headers.insert(
+        "Accept",
+        reqwest::header::HeaderValue::from_static("application/vnd.github+json"),
+    );
+    headers.insert(
+        "Authorization",
+        reqwest::header::HeaderValue::from_str(&user.get_auth_header())
+            .unwrap_or_else(|_| reqwest::header::HeaderValue::from_static("")),
+    );
+    return headers;
+}
+
+/// Runs a GraphQL query, the API needs an authenticated user
+async fn post_graphql(
+    user: &git::User,
+    query: &str,
+    variables: serde_json::Value,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let client = reqwest::Client::new();
+    let res = client
+        .post(GRAPHQL_URL)
+        .headers(request_headers(user))
+        .json(&serde_json::json!({ "query": query, "variables": variables }))
+        .send()
+        .await?;
+    return Ok(res.json::<serde_json::Value>().await?);
+}
+
+/// Sends the request with the body, with the message of the response as the error
+async fn send_data(
+    method: reqwest::Method,
+    url: &str,
+    user: &git::User,
+    body: serde_json::Value,
+) -> Result<(), String> {
+    let mut req = reqwest::Client::new()
+        .request(method, url)
+        .headers(request_headers(user));
+    if !body.is_null() {
+        req = req.json(&body);
+    }
+    let res = req.send().await.map_err(|e| e.to_string())?;
+    let status = res.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let v: serde_json::Value = res.json().await.unwrap_or_default();
+    return match v["message"].as_str() {
+        Some(message) => Err(message.to_string()),
+        None => Err(status.to_string()),
+    };
+}
+
 pub async fn fetch_data(
     url: &str,
     user: &git::User,
+) -> Result<(serde_json::Value, u16), Box<dyn std::error::Error>> {
+    return fetch_data_pages(url, user, None).await;
+}
+
+/// Fetches at most `max_pages` pages, or all pages when `None`
+pub async fn fetch_data_pages(
+    url: &str,
+    user: &git::User,
+    max_pages: Option<usize>,
 ) -> Result<(serde_json::Value, u16), Box<dyn std::error::Error>> {
     // if !user.fetch() {
     //     println!("Error: Rate limit reached");
@@ -200,25 +1216,13 @@ pub async fn fetch_data(
     let mut fetch_url = url.to_string();
     let mut data: serde_json::Value = serde_json::Value::Null;
     let mut status: u16;
+    let mut pages: usize = 0;
 
     loop {
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            "User-Agent",
-            reqwest::header::HeaderValue::from_static("gierm"),
-        );
-        headers.insert(
-            "Accept",
-            reqwest::header::HeaderValue::from_static("application/vnd.github+json"),
-        );
-        headers.insert(
-            "Authorization",
-            reqwest::header::HeaderValue::from_str(&user.get_auth_header())
-                .unwrap_or_else(|_| reqwest::header::HeaderValue::from_static("")),
-        );
+        pages += 1;
         let res = client
             .get(fetch_url)
-            .headers(headers)
+            .headers(request_headers(user))
             .query(&[("per_page", PER_PAGE)])
             .send()
             .await;
@@ -243,7 +1247,15 @@ pub async fn fetch_data(
                         serde_json::Value::Object(ref mut map) => {
                             if let serde_json::Value::Object(page_object) = v {
                                 for (k, v) in page_object {
-                                    map.insert(k, v);
+                                    match (map.get_mut(&k), v) {
+                                        (
+                                            Some(serde_json::Value::Array(items)),
+                                            serde_json::Value::Array(page_items),
+                                        ) => items.extend(page_items),
+                                        (_, v) => {
+                                            map.insert(k, v);
+                                        }
+                                    }
                                 }
                             } else {
                                 return Err("Expected an object".into());
@@ -258,6 +1270,9 @@ pub async fn fetch_data(
                         return Err(Box::new(e));
                     }
                 }
+                if max_pages.is_some_and(|max| pages >= max) {
+                    return Ok((data, status));
+                }
                 if let Some(link_header_value) = link_header {
                     if let Some(next_url) = extract_next_url(&link_header_value) {
                         fetch_url = next_url;
@@ -8,6 +8,8 @@ pub struct CLArgs {
     pub command: Option<String>,
     pub username: Option<String>,
     pub repo: Option<String>,
+    pub org: Option<String>,
+    pub root: Option<String>,
 }
 
 impl CLArgs {
@@ -16,6 +18,8 @@ impl CLArgs {
             command: None,
             username: None,
             repo: None,
+            org: None,
+            root: None,
         }
     }
 }
@@ -65,6 +69,26 @@ pub fn get_cl_args() -> Result<CLArgs, ArgsError> {
                     }
                 }
             }
+            "-o" | "--org" => {
+                if let Some(a) = arg_iter.peek() {
+                    if !a.starts_with("-") {
+                        if cl_args.org.is_none() {
+                            cl_args.org = Some(a.to_string());
+                        }
+                        arg_iter.next();
+                    }
+                }
+            }
+            "--root" => {
+                if let Some(a) = arg_iter.peek() {
+                    if !a.starts_with("-") {
+                        if cl_args.root.is_none() {
+                            cl_args.root = Some(a.to_string());
+                        }
+                        arg_iter.next();
+                    }
+                }
+            }
             "-h" | "--help" => {
                 return Err(ArgsError::Help);
             }
@@ -0,0 +1,12 @@
+use base64::Engine;
+use std::io::Write;
+
+/// Copies the text with the OSC 52 escape sequence, which the terminal
+/// forwards to the system clipboard also over SSH
+pub fn copy(text: &String) -> std::io::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut cout = std::io::stdout();
+    write!(cout, "\x1b]52;c;{}\x07", encoded)?;
+    cout.flush()?;
+    return Ok(());
+}
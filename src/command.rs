@@ -20,6 +20,7 @@ pub struct Cmd {
     cmd_type: CmdType,
     pub cmd: String,
     pub args: Vec<String>,
+    pub envs: Vec<(String, String)>,
 }
 
 impl Cmd {
@@ -28,6 +29,7 @@ impl Cmd {
             cmd_type: CmdType::DEFAULT,
             cmd,
             args,
+            envs: Vec::new(),
         }
     }
 
@@ -42,6 +44,7 @@ impl Cmd {
             cmd_type,
             cmd: "git".to_string(),
             args,
+            envs: Vec::new(),
         }
     }
 
@@ -49,6 +52,10 @@ impl Cmd {
         self.args = args;
     }
 
+    pub fn set_env(&mut self, key: &str, value: &str) {
+        self.envs.push((key.to_string(), value.to_string()));
+    }
+
     pub fn push_arg(&mut self, arg: String) {
         if !arg.is_empty() {
             self.args.push(arg);
@@ -70,11 +77,27 @@ impl Cmd {
         return cmd_str;
     }
 
+    /// Starts the command in the background without waiting for it
+    pub fn spawn(&self) -> Result<(), (CmdError, String)> {
+        let mut command = Command::new(self.cmd.clone());
+        command
+            .args(&self.args)
+            .envs(self.envs.clone())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        match command.spawn() {
+            Ok(_) => Ok(()),
+            Err(e) => Err((CmdError::CmdExecError, e.to_string())),
+        }
+    }
+
     pub fn exec(&self, capture_output: bool) -> Result<String, (CmdError, String)> {
         let mut command = Command::new(self.cmd.clone());
         self.args.iter().for_each(|arg| {
             command.arg(arg);
         });
+        command.envs(self.envs.clone());
         if !capture_output {
             command.stdout(Stdio::inherit()).stderr(Stdio::inherit());
         }
@@ -96,3 +119,24 @@ impl Cmd {
         }
     }
 }
+
+/// Git command run in the directory
+pub fn git_in(dir: &std::path::Path, args: &[&str]) -> Cmd {
+    let mut cmd_args: Vec<String> = vec!["-C".to_string(), dir.to_string_lossy().to_string()];
+    args.iter().for_each(|a| cmd_args.push(a.to_string()));
+    return Cmd::new("git".to_string(), cmd_args);
+}
+
+/// Opens the url with `$BROWSER`, or the platform opener when it is not set
+pub fn open_url(url: &String) -> Result<(), (CmdError, String)> {
+    let browser = std::env::var("BROWSER").unwrap_or(
+        match cfg!(target_os = "macos") {
+            true => "open",
+            false => "xdg-open",
+        }
+        .to_string(),
+    );
+    let mut cmd = Cmd::from_str(browser).ok_or((CmdError::CmdExecError, "".to_string()))?;
+    cmd.push_arg(url.clone());
+    return cmd.spawn();
+}
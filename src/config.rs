@@ -0,0 +1,138 @@
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+
+pub const CONFIG_FILE: &str = ".giermconfig";
+pub const CONFIG_PATHS: [&str; 2] = ["/", "/.config/gierm/"];
+pub const STATE_FILE: &str = "/.config/gierm/state";
+
+#[derive(Debug)]
+pub struct Config {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub sync_include: Vec<String>,
+    pub sync_exclude: Vec<String>,
+    pub keybindings: Vec<(String, String)>, // Action name and its keys
+    pub theme: Option<String>,
+    pub theme_styles: Vec<(String, String)>, // Style name and its value
+    pub layout: Option<String>,
+}
+
+impl Config {
+    pub fn new(username: Option<String>, password: Option<String>) -> Self {
+        Self {
+            username,
+            password,
+            sync_include: Vec::new(),
+            sync_exclude: Vec::new(),
+            keybindings: Vec::new(),
+            theme: None,
+            theme_styles: Vec::new(),
+            layout: None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "username" => self.username = Some(value.to_string()),
+            "password" => self.password = Some(value.to_string()),
+            "sync_include" => self.sync_include = split_list(value),
+            "sync_exclude" => self.sync_exclude = split_list(value),
+            "theme" => self.theme = Some(value.to_string()),
+            "layout" => self.layout = Some(value.to_string()),
+            _ if key.starts_with("theme.") => {
+                self.theme_styles
+                    .push((key["theme.".len()..].to_string(), value.to_string()));
+            }
+            _ if key.starts_with("bind.") => {
+                self.keybindings
+                    .push((key["bind.".len()..].to_string(), value.to_string()));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    return value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+}
+
+pub fn home_dir() -> String {
+    return std::env::var("HOME").unwrap_or("~".to_string());
+}
+
+/// Path with a leading `~` replaced by the home directory
+pub fn expand_home(path: &str) -> std::path::PathBuf {
+    if path == "~" || path.starts_with("~/") {
+        return std::path::PathBuf::from(format!("{}{}", home_dir(), &path[1..]));
+    }
+    return std::path::PathBuf::from(path);
+}
+
+pub fn find_config_file() -> Option<Config> {
+    let mut file: Option<File> = None;
+    for path in CONFIG_PATHS.iter() {
+        match File::open(format!("{}{}{}", home_dir(), path, CONFIG_FILE)) {
+            Ok(f) => {
+                file = Some(f);
+                break;
+            }
+            Err(_) => continue,
+        }
+    }
+
+    let reader = match file {
+        Some(file) => BufReader::new(file),
+        None => return None,
+    };
+
+    let mut config: Config = Config::new(None, None);
+
+    for line in reader.lines() {
+        match line {
+            Ok(l) => {
+                if let Some(eq_pos) = l.find('=') {
+                    let (key, value) = l.split_at(eq_pos);
+                    config.set(&key.trim().to_lowercase(), value[1..].trim());
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    return Some(config);
+}
+
+/// Key value pairs saved by the previous session
+pub fn load_state() -> Vec<(String, String)> {
+    let file = match File::open(format!("{}{}", home_dir(), STATE_FILE)) {
+        Ok(f) => f,
+        Err(_) => return Vec::new(),
+    };
+    return BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|l| {
+            let (key, value) = l.split_once('=')?;
+            Some((key.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+}
+
+/// Replaces the given keys in the state file, keeping the others
+pub fn save_state(entries: Vec<(String, String)>) {
+    let mut state = load_state();
+    state.retain(|(k, _)| !entries.iter().any(|(key, _)| key == k));
+    state.extend(entries);
+    let path = std::path::PathBuf::from(format!("{}{}", home_dir(), STATE_FILE));
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let content: String = state
+        .iter()
+        .map(|(k, v)| format!("{}={}\n", k, v))
+        .collect();
+    let _ = std::fs::write(path, content);
+}
@@ -1,35 +1,91 @@
+use crate::fuzzy::{match_pattern, FuzzyMatch};
 use crate::tui::StateL;
+use ratatui::{
+    style::Style,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+pub struct FilterMatch {
+    pub item: String,
+    pub positions: Vec<usize>,
+    pub group: String,
+}
 
 pub struct FilterList {
     pub state: StateL,
     pub list: Vec<String>,
+    pub groups: Vec<String>, // Group of each item, empty when not grouped
     pub filter: String,
+    pub view: ListState, // Drawn rows with the group headers, keeps the scroll offset
 }
 
 impl FilterList {
     pub fn new(list: Vec<String>, filter: String) -> Self {
         Self {
             state: StateL::new(list.len()),
+            view: ListState::default(),
             list,
+            groups: Vec::new(),
             filter,
         }
     }
 
-    pub fn get_filtered(&mut self) -> Vec<String> {
-        let l: Vec<String> = self
+    fn group_of(&self, i: usize) -> String {
+        return self.groups.get(i).cloned().unwrap_or_default();
+    }
+
+    /// Matching items ranked by match quality, with the matched char indices.
+    /// Grouped items stay in their group in the order the groups appear in the list.
+    pub fn get_matches(&mut self) -> Vec<FilterMatch> {
+        let mut matches: Vec<(usize, &String, FuzzyMatch)> = self
             .list
-            .clone()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| match_pattern(&self.filter, item).map(|m| (i, item, m)))
+            .collect();
+        if !self.filter.trim().is_empty() {
+            matches.sort_by_key(|(i, item, m)| (std::cmp::Reverse(m.score), item.len(), *i));
+        }
+        if !self.groups.is_empty() {
+            matches.sort_by_key(|(i, _, _)| {
+                let group = self.group_of(*i);
+                self.groups.iter().position(|g| *g == group)
+            });
+        }
+        let l: Vec<FilterMatch> = matches
             .into_iter()
-            .filter(|rn| rn.to_lowercase().contains(&self.filter.to_lowercase()))
+            .map(|(i, item, m)| FilterMatch {
+                item: item.clone(),
+                positions: m.positions,
+                group: self.group_of(i),
+            })
             .collect();
         self.state.new_size(l.len());
         return l;
     }
 
+    pub fn get_filtered(&mut self) -> Vec<String> {
+        return self.get_matches().into_iter().map(|m| m.item).collect();
+    }
+
     pub fn set_list(&mut self, new_list: Vec<String>) {
         self.list = new_list;
+        self.groups = Vec::new();
         self.set_filter("".to_string());
-        self.state.state = ratatui::widgets::ListState::default();
+        self.state.state = ListState::default();
+        self.view = ListState::default();
+    }
+
+    /// Replaces the items keeping the filter and the selected item
+    pub fn reorder(&mut self, new_list: Vec<String>, groups: Vec<String>) {
+        let selected = self
+            .get_index()
+            .and_then(|i| self.get_filtered().get(i).cloned());
+        self.list = new_list;
+        self.groups = groups;
+        let index = selected.and_then(|s| self.get_filtered().iter().position(|i| *i == s));
+        self.state.state.select(index);
     }
 
     pub fn set_filter(&mut self, filter: String) {
@@ -47,4 +103,84 @@ impl FilterList {
     pub fn get_index(&self) -> Option<usize> {
         return self.state.get_selected_index();
     }
+
+    /// State of the drawn rows with the row of the selected item selected
+    pub fn view_state(&mut self, selected_row: Option<usize>) -> &mut ListState {
+        self.view.select(selected_row);
+        return &mut self.view;
+    }
+}
+
+/// Splits the item into spans with the matched chars styled
+pub fn highlight_matches(item: &str, positions: &[usize], style: Style) -> Line<'static> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut current = String::new();
+    let mut current_matched = false;
+    for (i, c) in item.chars().enumerate() {
+        let matched = positions.contains(&i);
+        if matched != current_matched && !current.is_empty() {
+            spans.push(match current_matched {
+                true => Span::styled(current.clone(), style),
+                false => Span::raw(current.clone()),
+            });
+            current.clear();
+        }
+        current_matched = matched;
+        current.push(c);
+    }
+    spans.push(match current_matched {
+        true => Span::styled(current, style),
+        false => Span::raw(current),
+    });
+    return Line::from(spans);
+}
+
+/// List rows with a header at the start of each group, and the row of the
+/// selected item. Headers go after the group when the list is drawn bottom to top.
+pub fn list_items<'a>(
+    matches: &[FilterMatch],
+    selected: Option<usize>,
+    match_style: Style,
+    header_style: Style,
+    bottom_to_top: bool,
+    suffix: impl Fn(&str) -> Vec<Span<'a>>,
+) -> (Vec<ListItem<'a>>, Option<usize>) {
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut selected_row: Option<usize> = None;
+    let header =
+        |group: &String| ListItem::new(Line::from(Span::styled(group.clone(), header_style)));
+    for (i, m) in matches.iter().enumerate() {
+        let starts_group = i == 0 || matches[i - 1].group != m.group;
+        if !bottom_to_top && starts_group && !m.group.is_empty() {
+            items.push(header(&m.group));
+        }
+        if selected == Some(i) {
+            selected_row = Some(items.len());
+        }
+        let mut line = highlight_matches(&m.item, &m.positions, match_style);
+        line.spans.extend(suffix(&m.item));
+        items.push(ListItem::new(line));
+        let ends_group = i + 1 == matches.len() || matches[i + 1].group != m.group;
+        if bottom_to_top && ends_group && !m.group.is_empty() {
+            items.push(header(&m.group));
+        }
+    }
+    return (items, selected_row);
+}
+
+/// Match index shown on each row of `list_items`, None for group headers
+pub fn item_rows(matches: &[FilterMatch], bottom_to_top: bool) -> Vec<Option<usize>> {
+    let mut rows: Vec<Option<usize>> = Vec::new();
+    for (i, m) in matches.iter().enumerate() {
+        let starts_group = i == 0 || matches[i - 1].group != m.group;
+        if !bottom_to_top && starts_group && !m.group.is_empty() {
+            rows.push(None);
+        }
+        rows.push(Some(i));
+        let ends_group = i + 1 == matches.len() || matches[i + 1].group != m.group;
+        if bottom_to_top && ends_group && !m.group.is_empty() {
+            rows.push(None);
+        }
+    }
+    return rows;
 }
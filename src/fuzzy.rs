@@ -0,0 +1,370 @@
+// fzf style matching with the extended search syntax:
+// `term` fuzzy, `'term` exact, `^term` prefix, `term$` suffix, `!term` negation
+
+const SCORE_MATCH: i64 = 16;
+const SCORE_GAP_START: i64 = -3;
+const SCORE_GAP_EXTENSION: i64 = -1;
+const BONUS_BOUNDARY: i64 = SCORE_MATCH / 2;
+const BONUS_SEGMENT: i64 = BONUS_BOUNDARY + 2;
+const BONUS_CAMEL: i64 = BONUS_BOUNDARY - 1;
+const BONUS_CONSECUTIVE: i64 = -(SCORE_GAP_START + SCORE_GAP_EXTENSION);
+const BONUS_FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+#[derive(Debug)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub positions: Vec<usize>, // Char indices of the matched characters
+}
+
+impl FuzzyMatch {
+    fn empty() -> Self {
+        Self {
+            score: 0,
+            positions: Vec::new(),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum TermType {
+    Fuzzy,
+    Exact,
+    Prefix,
+    Suffix,
+}
+
+struct Term {
+    text: Vec<char>,
+    term_type: TermType,
+    inverse: bool,
+}
+
+impl Term {
+    fn parse(term: &str) -> Option<Self> {
+        let mut text = term.to_lowercase();
+        let inverse = text.starts_with('!');
+        if inverse {
+            text.remove(0);
+        }
+        let mut term_type = match inverse {
+            true => TermType::Exact,
+            false => TermType::Fuzzy,
+        };
+        if text.starts_with('\'') {
+            text.remove(0);
+            term_type = TermType::Exact;
+        } else if text.starts_with('^') {
+            text.remove(0);
+            term_type = TermType::Prefix;
+        } else if text.ends_with('$') && text.len() > 1 {
+            text.pop();
+            term_type = TermType::Suffix;
+        }
+        if text.is_empty() {
+            return None;
+        }
+        return Some(Self {
+            text: text.chars().collect(),
+            term_type,
+            inverse,
+        });
+    }
+}
+
+fn char_bonus(prev: Option<char>, c: char) -> i64 {
+    match prev {
+        None => BONUS_BOUNDARY,
+        Some('/') => BONUS_SEGMENT,
+        Some(p) if !p.is_alphanumeric() => BONUS_BOUNDARY,
+        Some(p) if p.is_lowercase() && c.is_uppercase() => BONUS_CAMEL,
+        Some(p) if !p.is_ascii_digit() && c.is_ascii_digit() => BONUS_CAMEL,
+        _ => 0,
+    }
+}
+
+fn bonuses(item: &[char]) -> Vec<i64> {
+    return (0..item.len())
+        .map(|i| char_bonus(if i == 0 { None } else { Some(item[i - 1]) }, item[i]))
+        .collect();
+}
+
+/// Best scoring subsequence match of `pattern` in `item`, both lowercased
+fn fuzzy_match(pattern: &[char], item: &[char], bonus: &[i64]) -> Option<FuzzyMatch> {
+    let (m, n) = (pattern.len(), item.len());
+    if m == 0 {
+        return Some(FuzzyMatch::empty());
+    }
+    if m > n {
+        return None;
+    }
+    // scores[i][j]: best score with pattern[i] matched at item[j]
+    let mut scores: Vec<Vec<Option<i64>>> = vec![vec![None; n]; m];
+    let mut from: Vec<Vec<usize>> = vec![vec![0; n]; m];
+
+    for i in 0..m {
+        // Best score of an earlier match that leaves a gap before j
+        let mut gap: Option<(i64, usize)> = None;
+        for j in i..n {
+            if let Some((g, k)) = gap {
+                gap = Some((g + SCORE_GAP_EXTENSION, k));
+            }
+            if i > 0 && j >= 2 {
+                if let Some(s) = scores[i - 1][j - 2] {
+                    let opened = s + SCORE_GAP_START;
+                    if gap.map_or(true, |(g, _)| opened > g) {
+                        gap = Some((opened, j - 2));
+                    }
+                }
+            }
+            if pattern[i] != item[j] {
+                continue;
+            }
+            if i == 0 {
+                scores[i][j] = Some(SCORE_MATCH + bonus[j] * BONUS_FIRST_CHAR_MULTIPLIER);
+                continue;
+            }
+            let consecutive = match j > 0 {
+                true => scores[i - 1][j - 1].map(|s| s + BONUS_CONSECUTIVE.max(bonus[j])),
+                false => None,
+            };
+            let gapped = gap.map(|(g, _)| g + bonus[j]);
+            match (consecutive, gapped) {
+                (Some(c), Some(g)) if g > c => {
+                    scores[i][j] = Some(SCORE_MATCH + g);
+                    from[i][j] = gap.map(|(_, k)| k).unwrap_or(0);
+                }
+                (Some(c), _) => {
+                    scores[i][j] = Some(SCORE_MATCH + c);
+                    from[i][j] = j - 1;
+                }
+                (None, Some(g)) => {
+                    scores[i][j] = Some(SCORE_MATCH + g);
+                    from[i][j] = gap.map(|(_, k)| k).unwrap_or(0);
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
+    let (mut j, score) = (0..n)
+        .filter_map(|j| scores[m - 1][j].map(|s| (j, s)))
+        .max_by_key(|(j, s)| (*s, std::cmp::Reverse(*j)))?;
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+    return Some(FuzzyMatch { score, positions });
+}
+
+fn exact_match(term: &Term, item: &[char], bonus: &[i64]) -> Option<FuzzyMatch> {
+    let len = term.text.len();
+    if len > item.len() {
+        return None;
+    }
+    let starts: Vec<usize> = match term.term_type {
+        TermType::Prefix => vec![0],
+        TermType::Suffix => vec![item.len() - len],
+        _ => (0..=item.len() - len).collect(),
+    };
+    return starts
+        .into_iter()
+        .filter(|&s| item[s..s + len] == term.text[..])
+        .map(|s| FuzzyMatch {
+            score: SCORE_MATCH * len as i64
+                + bonus[s] * BONUS_FIRST_CHAR_MULTIPLIER
+                + BONUS_CONSECUTIVE * (len as i64 - 1),
+            positions: (s..s + len).collect(),
+        })
+        .max_by_key(|m| m.score);
+}
+
+/// Matches `owner/repo` style terms segment by segment against items with a `/`
+fn segment_match(term: &Term, item: &[char], bonus: &[i64]) -> Option<FuzzyMatch> {
+    let term_split = term.text.iter().position(|c| *c == '/')?;
+    let item_split = item.iter().position(|c| *c == '/')?;
+    let owner = fuzzy_match(
+        &term.text[..term_split],
+        &item[..item_split],
+        &bonus[..item_split],
+    )?;
+    let repo = fuzzy_match(
+        &term.text[term_split + 1..],
+        &item[item_split + 1..],
+        &bonus[item_split + 1..],
+    )?;
+    let mut positions = owner.positions;
+    positions.push(item_split);
+    positions.extend(repo.positions.iter().map(|p| p + item_split + 1));
+    return Some(FuzzyMatch {
+        score: owner.score + repo.score + SCORE_MATCH + BONUS_SEGMENT,
+        positions,
+    });
+}
+
+fn term_match(term: &Term, item: &[char], bonus: &[i64]) -> Option<FuzzyMatch> {
+    if term.term_type != TermType::Fuzzy {
+        return exact_match(term, item, bonus);
+    }
+    return segment_match(term, item, bonus).or_else(|| fuzzy_match(&term.text, item, bonus));
+}
+
+/// Matches every whitespace separated term of the pattern against the item
+pub fn match_pattern(pattern: &str, item: &str) -> Option<FuzzyMatch> {
+    let chars: Vec<char> = item.to_lowercase().chars().collect();
+    let bonus = bonuses(&item.chars().collect::<Vec<char>>());
+    if chars.len() != bonus.len() {
+        // Lowercasing changed the length, positions would not line up
+        return item
+            .to_lowercase()
+            .contains(&pattern.to_lowercase())
+            .then(FuzzyMatch::empty);
+    }
+    let mut result = FuzzyMatch::empty();
+    for term in pattern.split_whitespace().filter_map(Term::parse) {
+        let m = term_match(&term, &chars, &bonus);
+        match (m, term.inverse) {
+            (Some(_), true) | (None, false) => return None,
+            (None, true) => {}
+            (Some(m), false) => {
+                result.score += m.score;
+                result.positions.extend(m.positions);
+            }
+        }
+    }
+    result.positions.sort();
+    result.positions.dedup();
+    return Some(result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(pattern: &str, item: &str) -> i64 {
+        return match_pattern(pattern, item).expect("no match").score;
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        let m = match_pattern("", "gierm").unwrap();
+        assert_eq!(m.score, 0);
+        assert!(m.positions.is_empty());
+        assert!(match_pattern("   ", "").is_some());
+    }
+
+    #[test]
+    fn fuzzy_matches_subsequence() {
+        let m = match_pattern("grm", "gierm").unwrap();
+        assert_eq!(m.positions, vec![0, 3, 4]);
+        assert!(match_pattern("mg", "gierm").is_none());
+        assert!(match_pattern("gierms", "gierm").is_none());
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        assert_eq!(
+            match_pattern("GIE", "gierm").unwrap().positions,
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            match_pattern("gie", "GiErm").unwrap().positions,
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn exact_term() {
+        let m = match_pattern("'ier", "gierm").unwrap();
+        assert_eq!(m.positions, vec![1, 2, 3]);
+        assert!(match_pattern("'grm", "gierm").is_none());
+    }
+
+    #[test]
+    fn prefix_term() {
+        assert_eq!(match_pattern("^gi", "gierm").unwrap().positions, vec![0, 1]);
+        assert!(match_pattern("^ie", "gierm").is_none());
+    }
+
+    #[test]
+    fn suffix_term() {
+        assert_eq!(match_pattern("rm$", "gierm").unwrap().positions, vec![3, 4]);
+        assert!(match_pattern("er$", "gierm").is_none());
+        // A lone `$` is matched as is
+        assert!(match_pattern("$", "cost$").is_some());
+    }
+
+    #[test]
+    fn negated_term() {
+        assert!(match_pattern("!test", "gierm").is_some());
+        assert!(match_pattern("!ier", "gierm").is_none());
+        // Negation is exact, not fuzzy
+        assert!(match_pattern("!grm", "gierm").is_some());
+        assert!(match_pattern("!^ie", "gierm").is_some());
+        assert!(match_pattern("!^gi", "gierm").is_none());
+    }
+
+    #[test]
+    fn every_term_must_match() {
+        let m = match_pattern("gi rm$", "gierm").unwrap();
+        assert_eq!(m.positions, vec![0, 1, 3, 4]);
+        assert!(match_pattern("gi xyz", "gierm").is_none());
+        assert!(match_pattern("gi !rm", "gierm").is_none());
+    }
+
+    #[test]
+    fn owner_repo_terms_match_by_segment() {
+        let m = match_pattern("lep/gi", "lepton9/gierm").unwrap();
+        assert_eq!(m.positions, vec![0, 1, 2, 7, 8, 9]);
+        assert!(match_pattern("gi/lep", "lepton9/gierm").is_none());
+    }
+
+    #[test]
+    fn unicode_positions_are_char_indices() {
+        let m = match_pattern("rg", "Ärger").unwrap();
+        assert_eq!(m.positions, vec![1, 2]);
+        assert_eq!(match_pattern("ä", "Ärger").unwrap().positions, vec![0]);
+        assert_eq!(
+            match_pattern("本語", "日本語").unwrap().positions,
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn unicode_case_changing_length_falls_back_to_contains() {
+        // `İ` lowercases to two chars
+        let m = match_pattern("stan", "İstanbul").unwrap();
+        assert!(m.positions.is_empty());
+        assert!(match_pattern("bx", "İstanbul").is_none());
+    }
+
+    #[test]
+    fn consecutive_matches_score_higher() {
+        assert!(score("abc", "xabcx") > score("abc", "xaxbxcx"));
+        assert!(score("ier", "gierm") > score("ier", "gixexr"));
+    }
+
+    #[test]
+    fn boundary_matches_score_higher() {
+        // Start of a word after a separator beats the middle of a word
+        assert!(score("b", "a-b") > score("b", "ab"));
+        // camelCase humps beat the middle of a word
+        assert!(score("b", "aBc") > score("b", "abc"));
+        // The start of a path segment beats other boundaries
+        assert!(score("b", "a/b") > score("b", "a-b"));
+        // The first char of the item is a boundary
+        assert!(score("g", "gierm") > score("e", "gierm"));
+    }
+
+    #[test]
+    fn best_alignment_is_chosen() {
+        // The boundary `b` of `bar` is preferred over the earlier one inside `abc`
+        let m = match_pattern("bar", "abc_bar").unwrap();
+        assert_eq!(m.positions, vec![4, 5, 6]);
+    }
+
+    #[test]
+    fn shorter_gaps_score_higher() {
+        assert!(score("ac", "abc") > score("ac", "abbbbc"));
+    }
+}
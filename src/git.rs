@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -7,7 +7,15 @@ pub struct GitUser {
     pub name: String,
     pub email: String,
     pub bio: String,
-    pub repos: HashMap<String, Repo>,
+    pub is_org: bool,
+    pub repos: Repos,
+    pub orgs: Vec<String>,
+    pub members: Vec<String>,
+    pub teams: Vec<String>,
+    pub followers: i32,
+    pub following: i32,
+    pub created_at: DateTime<Utc>,
+    pub contributions: Vec<(NaiveDate, i32)>, // Contributions per day of the last year
 }
 
 impl GitUser {
@@ -17,8 +25,69 @@ impl GitUser {
             name,
             email,
             bio,
-            repos: HashMap::new(),
+            is_org: false,
+            repos: Repos::new(),
+            orgs: Vec::new(),
+            members: Vec::new(),
+            teams: Vec::new(),
+            followers: 0,
+            following: 0,
+            created_at: DateTime::default(),
+            contributions: Vec::new(),
+        }
+    }
+
+    /// Contributions per day, or the dates of the fetched commits of the user
+    /// when the contribution calendar is not available
+    pub fn contribution_days(&self) -> Vec<(NaiveDate, i32)> {
+        if !self.contributions.is_empty() {
+            return self.contributions.clone();
         }
+        return self
+            .repos
+            .iter()
+            .flat_map(|r| r.commits.iter())
+            .filter(|c| c.committer.to_lowercase() == self.username.to_lowercase())
+            .map(|c| (c.date.date_naive(), 1))
+            .collect();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepoFilter {
+    All,
+    Sources,
+    Forks,
+    Archived,
+}
+
+impl RepoFilter {
+    pub fn next(&self) -> Self {
+        match self {
+            RepoFilter::All => RepoFilter::Sources,
+            RepoFilter::Sources => RepoFilter::Forks,
+            RepoFilter::Forks => RepoFilter::Archived,
+            RepoFilter::Archived => RepoFilter::All,
+        }
+    }
+
+    /// Value of the `type` query parameter of the org repos endpoint
+    pub fn api_type(&self) -> &str {
+        match self {
+            RepoFilter::Sources => "sources",
+            RepoFilter::Forks => "forks",
+            RepoFilter::All | RepoFilter::Archived => "all",
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        return match self {
+            RepoFilter::All => "all",
+            RepoFilter::Sources => "sources",
+            RepoFilter::Forks => "forks",
+            RepoFilter::Archived => "archived",
+        }
+        .to_string();
     }
 }
 
@@ -71,6 +140,27 @@ pub struct Repo {
     pub language: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub archived: bool,
+    pub fork: bool,
+    pub private: bool,
+    pub stars: i32,
+    pub forks: i32,
+    pub watchers: i32,
+    pub open_issues: i32,
+    pub size: i64, // Kilobytes
+    pub license: String,
+    pub topics: Vec<String>,
+    pub default_branch: String,
+    pub visibility: String,
+    pub languages: Vec<(String, i64)>, // Bytes of code per language, largest first
+    pub branches: Vec<(String, String)>, // Branch names and the shas of their heads
+    pub tags: Vec<(String, String)>,   // Tag names and the shas they point to
+    pub commit_filter: CommitFilter,
+    pub runs: Vec<WorkflowRun>, // Latest workflow runs
+    pub releases: Vec<Release>,
+    pub runs_fetched: bool, // Runs and releases are fetched when their block is first focused
+    pub releases_fetched: bool,
+    pub checks: HashMap<String, Option<Check>>, // Combined check state by commit sha, fetched for the shown commits
     pub commits: Vec<Commit>,
 }
 
@@ -95,11 +185,444 @@ impl Repo {
             language,
             created_at: created,
             updated_at: updated,
+            archived: false,
+            fork: false,
+            private: false,
+            stars: 0,
+            forks: 0,
+            watchers: 0,
+            open_issues: 0,
+            size: 0,
+            license: String::new(),
+            topics: Vec::new(),
+            default_branch: String::new(),
+            visibility: String::new(),
+            languages: Vec::new(),
+            branches: Vec::new(),
+            tags: Vec::new(),
+            commit_filter: CommitFilter::default(),
+            runs: Vec::new(),
+            releases: Vec::new(),
+            runs_fetched: false,
+            releases_fetched: false,
+            checks: HashMap::new(),
             commits: Vec::new(),
         }
     }
 }
 
+impl Repo {
+    /// Takes the metadata of the freshly fetched repo, keeping the commits, refs,
+    /// filter, runs, releases and check states fetched for this one
+    pub fn update_info(&mut self, fetched: Repo) {
+        self.description = fetched.description;
+        self.language = fetched.language;
+        self.created_at = fetched.created_at;
+        self.updated_at = fetched.updated_at;
+        self.archived = fetched.archived;
+        self.fork = fetched.fork;
+        self.private = fetched.private;
+        self.stars = fetched.stars;
+        self.forks = fetched.forks;
+        self.watchers = fetched.watchers;
+        self.open_issues = fetched.open_issues;
+        self.size = fetched.size;
+        self.license = fetched.license;
+        self.topics = fetched.topics;
+        self.default_branch = fetched.default_branch;
+        self.visibility = fetched.visibility;
+        self.languages = fetched.languages;
+    }
+
+    /// Short markers shown next to the repo name in lists
+    pub fn indicators(&self) -> Vec<&str> {
+        let mut indicators: Vec<&str> = Vec::new();
+        if self.private {
+            indicators.push("private");
+        }
+        if self.fork {
+            indicators.push("fork");
+        }
+        if self.archived {
+            indicators.push("archived");
+        }
+        return indicators;
+    }
+
+    /// Indices of the commits that pass the commit filter
+    pub fn visible_commits(&self) -> Vec<usize> {
+        return (0..self.commits.len())
+            .filter(|i| self.commit_filter.matches(&self.commits[*i]))
+            .collect();
+    }
+
+    /// Index in the commits of the row in the filtered commit list
+    pub fn visible_commit_index(&self, row: usize) -> Option<usize> {
+        return self
+            .commits
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| self.commit_filter.matches(c))
+            .nth(row)
+            .map(|(i, _)| i);
+    }
+
+    /// Row of the commit in the filtered commit list
+    pub fn commit_row(&self, sha: &String) -> Option<usize> {
+        return self
+            .commits
+            .iter()
+            .filter(|c| self.commit_filter.matches(c))
+            .position(|c| &c.sha == sha);
+    }
+
+    /// Names of the branches with the commit as their head
+    pub fn branches_at(&self, sha: &String) -> Vec<String> {
+        return self
+            .branches
+            .iter()
+            .filter(|(_, head)| head == sha)
+            .map(|(name, _)| name.clone())
+            .collect();
+    }
+
+    pub fn size_string(&self) -> String {
+        return match self.size {
+            s if s >= 1024 * 1024 => format!("{:.1} GB", s as f64 / (1024.0 * 1024.0)),
+            s if s >= 1024 => format!("{:.1} MB", s as f64 / 1024.0),
+            s => format!("{} KB", s),
+        };
+    }
+
+    pub fn language_name(&self) -> String {
+        return match self.language.as_str() {
+            "null" | "" => "No language".to_string(),
+            l => l.to_string(),
+        };
+    }
+}
+
+/// Filter of the commits of a repo. Words are matched against the message and `author:`
+/// (login or git name of the author), `sha:`, `after:` and `before:` against the fetched commits, while `author=`, `since=`,
+/// `until=` and `path=` are passed to the commits endpoint.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CommitFilter {
+    pub query: String,
+    text: String,
+    author: String,
+    sha: String,
+    after: Option<NaiveDate>,
+    before: Option<NaiveDate>,
+    pub params: Vec<(String, String)>, // Query parameters of the commits endpoint
+}
+
+impl CommitFilter {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let date = |value: &str| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", value))
+        };
+        let mut filter = CommitFilter {
+            query: query.trim().to_string(),
+            ..Default::default()
+        };
+        let mut words: Vec<&str> = Vec::new();
+        for word in query.split_whitespace() {
+            if let Some((key, value)) = word.split_once('=') {
+                let value = match key {
+                    "author" | "path" => value.to_string(),
+                    "since" => format!("{}T00:00:00Z", date(value)?),
+                    "until" => format!("{}T23:59:59Z", date(value)?),
+                    _ => return Err(format!("Unknown filter '{}='", key)),
+                };
+                filter.params.push((key.to_string(), value));
+                continue;
+            }
+            match word.split_once(':') {
+                Some(("author", value)) => filter.author = value.to_lowercase(),
+                Some(("sha", value)) => filter.sha = value.to_lowercase(),
+                Some(("after", value)) => filter.after = Some(date(value)?),
+                Some(("before", value)) => filter.before = Some(date(value)?),
+                _ => words.push(word),
+            }
+        }
+        filter.text = words.join(" ");
+        return Ok(filter);
+    }
+
+    /// If the filter hides some of the fetched commits
+    pub fn is_local(&self) -> bool {
+        return !self.text.is_empty()
+            || !self.author.is_empty()
+            || !self.sha.is_empty()
+            || self.after.is_some()
+            || self.before.is_some();
+    }
+
+    pub fn matches(&self, commit: &Commit) -> bool {
+        let date = commit.date.date_naive();
+        return (self.text.is_empty()
+            || crate::fuzzy::match_pattern(&self.text, &commit.message).is_some())
+            && commit.author.to_lowercase().contains(&self.author)
+            && commit.sha.starts_with(&self.sha)
+            && self.after.map_or(true, |d| date > d)
+            && self.before.map_or(true, |d| date < d);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepoSort {
+    Updated,
+    Created,
+    Name,
+    Stars,
+    Language,
+}
+
+impl RepoSort {
+    pub fn next(&self) -> Self {
+        match self {
+            RepoSort::Updated => RepoSort::Created,
+            RepoSort::Created => RepoSort::Name,
+            RepoSort::Name => RepoSort::Stars,
+            RepoSort::Stars => RepoSort::Language,
+            RepoSort::Language => RepoSort::Updated,
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        return match self {
+            RepoSort::Updated => "updated",
+            RepoSort::Created => "created",
+            RepoSort::Name => "name",
+            RepoSort::Stars => "stars",
+            RepoSort::Language => "language",
+        }
+        .to_string();
+    }
+
+    fn compare(&self, a: &Repo, b: &Repo) -> std::cmp::Ordering {
+        let by_name = a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        match self {
+            RepoSort::Updated => b.updated_at.cmp(&a.updated_at),
+            RepoSort::Created => b.created_at.cmp(&a.created_at),
+            RepoSort::Name => by_name,
+            RepoSort::Stars => b.stars.cmp(&a.stars).then(by_name),
+            RepoSort::Language => a.language_name().cmp(&b.language_name()).then(by_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RepoGroup {
+    None,
+    Owner,
+    Language,
+}
+
+impl RepoGroup {
+    pub fn next(&self) -> Self {
+        match self {
+            RepoGroup::None => RepoGroup::Owner,
+            RepoGroup::Owner => RepoGroup::Language,
+            RepoGroup::Language => RepoGroup::None,
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        return match self {
+            RepoGroup::None => "none",
+            RepoGroup::Owner => "owner",
+            RepoGroup::Language => "language",
+        }
+        .to_string();
+    }
+
+    /// Section header of the repo, empty when not grouping
+    pub fn label(&self, repo: &Repo) -> String {
+        return match self {
+            RepoGroup::None => "".to_string(),
+            RepoGroup::Owner => repo.user.clone(),
+            RepoGroup::Language => repo.language_name(),
+        };
+    }
+}
+
+/// Repos keyed by name that keep the order they were added in
+#[derive(Debug, Default)]
+pub struct Repos {
+    repos: Vec<Repo>,
+    index: HashMap<String, usize>, // Position of each repo by name
+}
+
+impl Repos {
+    pub fn new() -> Self {
+        Self {
+            repos: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    /// Adds the repo or replaces the one with the same name
+    pub fn insert(&mut self, repo: Repo) {
+        match self.index.get(&repo.name) {
+            Some(&i) => self.repos[i] = repo,
+            None => {
+                self.index.insert(repo.name.clone(), self.repos.len());
+                self.repos.push(repo);
+            }
+        }
+    }
+
+    pub fn get(&self, name: &String) -> Option<&Repo> {
+        return self.index.get(name).map(|&i| &self.repos[i]);
+    }
+
+    pub fn get_mut(&mut self, name: &String) -> Option<&mut Repo> {
+        return self.index.get(name).map(|&i| &mut self.repos[i]);
+    }
+
+    pub fn contains_key(&self, name: &String) -> bool {
+        return self.index.contains_key(name);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Repo> {
+        return self.repos.iter();
+    }
+
+    pub fn len(&self) -> usize {
+        return self.repos.len();
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&Repo) -> bool) {
+        self.repos.retain(f);
+        self.index = self
+            .repos
+            .iter()
+            .enumerate()
+            .map(|(i, r)| (r.name.clone(), i))
+            .collect();
+    }
+
+    /// Repo names in display order and the group of each, empty when not grouping
+    pub fn ordered_names(&self, sort: RepoSort, group: RepoGroup) -> (Vec<String>, Vec<String>) {
+        let sorted = self.sorted(sort, group);
+        let names: Vec<String> = sorted.iter().map(|r| r.name.clone()).collect();
+        let groups: Vec<String> = match group {
+            RepoGroup::None => Vec::new(),
+            _ => sorted.iter().map(|r| group.label(r)).collect(),
+        };
+        return (names, groups);
+    }
+
+    /// Repos ordered by the group label first and the sort key within a group
+    pub fn sorted(&self, sort: RepoSort, group: RepoGroup) -> Vec<&Repo> {
+        let mut repos: Vec<&Repo> = self.repos.iter().collect();
+        repos.sort_by(|a, b| {
+            group
+                .label(a)
+                .to_lowercase()
+                .cmp(&group.label(b).to_lowercase())
+                .then(sort.compare(a, b))
+        });
+        return repos;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchKind {
+    Repositories,
+    Code,
+    Issues,
+    Commits,
+    Users,
+}
+
+impl SearchKind {
+    pub fn next(&self) -> Self {
+        match self {
+            SearchKind::Repositories => SearchKind::Code,
+            SearchKind::Code => SearchKind::Issues,
+            SearchKind::Issues => SearchKind::Commits,
+            SearchKind::Commits => SearchKind::Users,
+            SearchKind::Users => SearchKind::Repositories,
+        }
+    }
+
+    /// Path segment of the search endpoint
+    pub fn to_string(&self) -> String {
+        return match self {
+            SearchKind::Repositories => "repositories",
+            SearchKind::Code => "code",
+            SearchKind::Issues => "issues",
+            SearchKind::Commits => "commits",
+            SearchKind::Users => "users",
+        }
+        .to_string();
+    }
+}
+
+#[derive(Debug)]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub owner: String, // User login for user results
+    pub repo: String,
+    pub title: String,
+    pub detail: String,
+    pub sha: String,
+}
+
+impl SearchResult {
+    pub fn new(kind: SearchKind, owner: String, repo: String, title: String) -> Self {
+        Self {
+            kind,
+            owner,
+            repo,
+            title,
+            detail: String::new(),
+            sha: String::new(),
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        if self.detail.is_empty() {
+            return self.title.clone();
+        }
+        return format!("{}  {}", self.title, self.detail);
+    }
+}
+
+pub fn get_user_url(user: &String) -> String {
+    return format!("https://github.com/{}", user);
+}
+
+pub fn get_repo_url(user: &String, repo: &String) -> String {
+    return format!("https://github.com/{}/{}", user, repo);
+}
+
+pub fn get_commit_url(user: &String, repo: &String, sha: &String) -> String {
+    return format!("https://github.com/{}/{}/commit/{}", user, repo, sha);
+}
+
+pub fn get_compare_url(user: &String, repo: &String, base: &String, head: &String) -> String {
+    return format!(
+        "https://github.com/{}/{}/compare/{}...{}",
+        user, repo, base, head
+    );
+}
+
+/// Shortens a commit sha and keeps branch and tag names
+pub fn short_ref(r: &String) -> String {
+    if r.len() == 40 && r.chars().all(|c| c.is_ascii_hexdigit()) {
+        return r.chars().take(8).collect();
+    }
+    return r.clone();
+}
+
+pub fn get_file_url(user: &String, repo: &String, sha: &String, path: &String) -> String {
+    return format!("https://github.com/{}/{}/blob/{}/{}", user, repo, sha, path);
+}
+
 pub fn get_clone_url(user: &String, repo: &String, ssh: bool) -> String {
     if ssh {
         return format!("git@github.com:{}/{}.git", user, repo);
@@ -113,7 +636,9 @@ pub struct Commit {
     pub message: String,
     pub sha: String,
     pub committer: String, // Username
+    pub author: String,    // Username, or the git name of an author without an account
     pub date: DateTime<Utc>,
+    pub parents: Vec<String>, // Shas of the parent commits
     pub info: Option<CommitInfo>,
 }
 
@@ -125,7 +650,9 @@ impl Commit {
             message,
             sha,
             committer,
+            author: String::new(),
             date: dt,
+            parents: Vec::new(),
             info: None,
         }
     }
@@ -160,6 +687,7 @@ pub struct CommitInfo {
     pub additions: i32,
     pub deletions: i32,
     pub files: Vec<File>,
+    pub checks: Vec<Check>,
 }
 
 impl CommitInfo {
@@ -169,6 +697,7 @@ impl CommitInfo {
             additions,
             deletions,
             files: Vec::new(),
+            checks: Vec::new(),
         }
     }
     pub fn default() -> Self {
@@ -177,10 +706,232 @@ impl CommitInfo {
             additions: 0,
             deletions: 0,
             files: Vec::new(),
+            checks: Vec::new(),
         }
     }
 }
 
+/// Changes between two commits or refs
+#[derive(Debug)]
+pub struct Comparison {
+    pub base: String,
+    pub head: String,
+    pub status: String, // ahead, behind, diverged or identical
+    pub ahead_by: i32,
+    pub behind_by: i32,
+    pub commits: Vec<Commit>, // Commits in head that are not in base, oldest first
+    pub info: CommitInfo,
+}
+
+pub fn parse_time(time: &str) -> Option<DateTime<Utc>> {
+    return DateTime::parse_from_rfc3339(time).ok().map(|t| t.into());
+}
+
+/// Time between the timestamps, until now when the end is missing
+pub fn duration_string(start: Option<DateTime<Utc>>, end: Option<DateTime<Utc>>) -> String {
+    let start = match start {
+        Some(start) => start,
+        None => return String::new(),
+    };
+    let seconds = (end.unwrap_or(Utc::now()) - start).num_seconds().max(0);
+    return match seconds {
+        s if s >= 3600 => format!("{}h {:02}m", s / 3600, s % 3600 / 60),
+        s if s >= 60 => format!("{}m {:02}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    };
+}
+
+/// Symbol of the state of a workflow run, job or step
+pub fn run_symbol(status: &str, conclusion: &str) -> &'static str {
+    return match (status, conclusion) {
+        ("completed", "success") => "✓",
+        ("completed", "failure" | "timed_out" | "startup_failure") => "✗",
+        ("completed", "cancelled") => "⊘",
+        ("completed", "skipped" | "neutral") => "-",
+        ("completed", _) => "?",
+        ("in_progress", _) => "●",
+        _ => "○",
+    };
+}
+
+/// Size in bytes in the largest unit it has at least one of
+pub fn bytes_string(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < units.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    return match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, units[unit]),
+    };
+}
+
+#[derive(Debug, Clone)]
+pub struct Asset {
+    pub name: String,
+    pub size: u64,
+    pub url: String, // API url, downloads the asset with an octet-stream Accept header
+    pub download_count: i64,
+    pub digest: String, // Like sha256:<hex>, empty for assets uploaded before digests
+}
+
+#[derive(Debug)]
+pub struct Release {
+    pub tag: String,
+    pub name: String,
+    pub notes: String,
+    pub author: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub published_at: Option<DateTime<Utc>>,
+    pub url: String,
+    pub assets: Vec<Asset>,
+}
+
+/// Time since the timestamp, like 5m ago
+pub fn relative_time(time: DateTime<Utc>) -> String {
+    let seconds = (Utc::now() - time).num_seconds().max(0);
+    return match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3600 => format!("{}m ago", s / 60),
+        s if s < 86400 => format!("{}h ago", s / 3600),
+        s if s < 86400 * 30 => format!("{}d ago", s / 86400),
+        s if s < 86400 * 365 => format!("{}mo ago", s / (86400 * 30)),
+        s => format!("{}y ago", s / (86400 * 365)),
+    };
+}
+
+/// Event in the activity feed of a user
+#[derive(Debug, Clone)]
+pub struct Event {
+    pub actor: String,
+    pub repo: String, // Full name of the repo, the fork of a fork event
+    pub summary: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub sha: String,                       // Head of a push
+    pub compare: Option<(String, String)>, // Base and head of a pull request
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub id: String,
+    pub repo: String,   // Full name of the repo
+    pub reason: String, // Like mention, review_requested or assign
+    pub kind: String,   // Type of the subject, like Issue, PullRequest or Commit
+    pub title: String,
+    pub url: String, // API url of the subject
+    pub unread: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Notification {
+    /// Last part of the subject url, the number of an issue or the sha of a commit
+    pub fn subject_id(&self) -> String {
+        return self.url.rsplit('/').next().unwrap_or("").to_string();
+    }
+
+    pub fn html_url(&self) -> String {
+        let repo_url = format!("https://github.com/{}", self.repo);
+        return match self.kind.as_str() {
+            "PullRequest" => format!("{}/pull/{}", repo_url, self.subject_id()),
+            "Issue" => format!("{}/issues/{}", repo_url, self.subject_id()),
+            "Commit" => format!("{}/commit/{}", repo_url, self.subject_id()),
+            "Release" => format!("{}/releases", repo_url),
+            "Discussion" => format!("{}/discussions", repo_url),
+            _ => repo_url,
+        };
+    }
+}
+
+/// Commit status or check run, statuses are kept in the states of a check run
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: String, // queued, in_progress or completed
+    pub conclusion: String,
+    pub url: String,
+}
+
+#[derive(Debug)]
+pub struct WorkflowRun {
+    pub id: u64,
+    pub workflow_id: u64,
+    pub name: String, // Name of the workflow
+    pub title: String,
+    pub number: i64,
+    pub status: String, // queued, in_progress or completed
+    pub conclusion: String,
+    pub branch: String,
+    pub event: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub url: String,
+    pub jobs: Vec<Job>,
+}
+
+impl WorkflowRun {
+    pub fn duration(&self) -> String {
+        let end = match self.status.as_str() {
+            "completed" => self.updated_at,
+            _ => None,
+        };
+        return duration_string(self.started_at, end);
+    }
+}
+
+#[derive(Debug)]
+pub struct Job {
+    pub id: u64,
+    pub name: String,
+    pub status: String,
+    pub conclusion: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Debug)]
+pub struct Step {
+    pub number: i64,
+    pub name: String,
+    pub status: String,
+    pub conclusion: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Lines of a file last changed by a commit
+#[derive(Debug)]
+pub struct BlameRange {
+    pub start: usize, // First line, counted from 1
+    pub end: usize,   // Last line
+    pub sha: String,
+    pub author: String,
+    pub date: DateTime<Utc>,
+    pub message: String,
+}
+
+/// Contents of a file at a commit with the commit that last changed each line
+#[derive(Debug)]
+pub struct Blame {
+    pub path: String,
+    pub lines: Vec<String>,
+    pub ranges: Vec<BlameRange>,
+}
+
+impl Blame {
+    /// Range of the line, counted from 0
+    pub fn range_at(&self, line: usize) -> Option<&BlameRange> {
+        return self
+            .ranges
+            .iter()
+            .find(|r| r.start <= line + 1 && line + 1 <= r.end);
+    }
+}
+
 #[derive(Debug)]
 pub struct File {
     pub name: String,
@@ -207,3 +958,70 @@ impl File {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(description: &str) -> Repo {
+        return Repo::new(
+            "me".to_string(),
+            "gierm".to_string(),
+            description.to_string(),
+            "Rust".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+            "2024-01-01T00:00:00Z".to_string(),
+        );
+    }
+
+    fn commit(sha: &str, author: &str) -> Commit {
+        let mut commit = Commit::new(
+            "fix".to_string(),
+            sha.to_string(),
+            "web-flow".to_string(),
+            "".to_string(),
+        );
+        commit.author = author.to_string();
+        return commit;
+    }
+
+    #[test]
+    fn author_filter_matches_author_not_committer() {
+        let filter = CommitFilter::parse("author:Bob").unwrap();
+        assert!(filter.matches(&commit("aaa", "bob")));
+        assert!(!filter.matches(&commit("bbb", "alice")));
+        assert!(!CommitFilter::parse("author:web")
+            .unwrap()
+            .matches(&commit("ccc", "alice")));
+    }
+
+    #[test]
+    fn info_refresh_keeps_fetched_data() {
+        let mut shown = repo("old");
+        shown.commits = vec![commit("aaa", "alice"), commit("bbb", "bob")];
+        shown.branches = vec![("main".to_string(), "aaa".to_string())];
+        shown.tags = vec![("v1".to_string(), "bbb".to_string())];
+        shown.commit_filter = CommitFilter::parse("author:bob").unwrap();
+        shown.checks.insert("aaa".to_string(), None);
+
+        let mut fetched = repo("new");
+        fetched.stars = 42;
+        fetched.topics = vec!["tui".to_string()];
+        fetched.languages = vec![("Rust".to_string(), 100)];
+        shown.update_info(fetched);
+
+        assert_eq!(shown.description, "new");
+        assert_eq!(shown.stars, 42);
+        assert_eq!(shown.topics, vec!["tui".to_string()]);
+        assert_eq!(shown.languages, vec![("Rust".to_string(), 100)]);
+        assert_eq!(shown.commits.len(), 2);
+        assert_eq!(shown.commit_filter.query, "author:bob");
+        assert_eq!(shown.visible_commits(), vec![1]);
+        assert_eq!(
+            shown.branches_at(&"aaa".to_string()),
+            vec!["main".to_string()]
+        );
+        assert_eq!(shown.tags.len(), 1);
+        assert!(shown.checks.contains_key("aaa"));
+    }
+}
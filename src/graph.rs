@@ -0,0 +1,99 @@
+use crate::git::Commit;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mark {
+    None,
+    Close, // A branch merges into the commit and ends
+    Open,  // A merged parent starts a new lane
+    Join,  // A merged parent goes on in a lane that already exists
+}
+
+/// Lanes of a `git log --graph` like view with one row for each commit,
+/// the commits are ordered from the newest like the commits endpoint returns them
+pub fn graph_rows(commits: &[Commit]) -> Vec<String> {
+    let mut lanes: Vec<Option<String>> = Vec::new(); // Sha expected next in each lane
+    let mut rows = Vec::with_capacity(commits.len());
+    for commit in commits {
+        let col = match lanes.iter().position(|l| l.as_ref() == Some(&commit.sha)) {
+            Some(i) => i,
+            None => match lanes.iter().position(|l| l.is_none()) {
+                Some(i) => i,
+                None => {
+                    lanes.push(None);
+                    lanes.len() - 1
+                }
+            },
+        };
+        let before: Vec<bool> = lanes.iter().map(|l| l.is_some()).collect();
+        let mut marks = vec![Mark::None; lanes.len()];
+        for (i, lane) in lanes.iter().enumerate() {
+            if i != col && lane.as_ref() == Some(&commit.sha) {
+                marks[i] = Mark::Close;
+            }
+        }
+        lanes[col] = commit.parents.first().cloned();
+        for parent in commit.parents.iter().skip(1) {
+            if let Some(i) = lanes.iter().position(|l| l.as_ref() == Some(parent)) {
+                if marks[i] == Mark::None && i != col {
+                    marks[i] = Mark::Join;
+                }
+                continue;
+            }
+            // A lane of a branch that ends here goes on with the parent
+            if let Some(i) = (0..lanes.len()).find(|&i| marks[i] == Mark::Close) {
+                lanes[i] = Some(parent.clone());
+                marks[i] = Mark::Join;
+                continue;
+            }
+            let free = (0..lanes.len()).find(|&i| lanes[i].is_none() && i != col);
+            let i = match free {
+                Some(i) => i,
+                None => {
+                    lanes.push(None);
+                    marks.push(Mark::None);
+                    lanes.len() - 1
+                }
+            };
+            lanes[i] = Some(parent.clone());
+            marks[i] = Mark::Open;
+        }
+
+        let marked: Vec<usize> = (0..marks.len())
+            .filter(|&i| marks[i] != Mark::None)
+            .collect();
+        let left = marked.iter().copied().chain([col]).min().unwrap_or(col);
+        let right = marked.iter().copied().chain([col]).max().unwrap_or(col);
+        let mut row = String::new();
+        for (i, mark) in marks.iter().enumerate() {
+            let passing = before.get(i).copied().unwrap_or(false);
+            let crossed = i > left && i < right;
+            let glyph = match mark {
+                _ if i == col && commit.parents.len() > 1 => "◉",
+                _ if i == col => "●",
+                Mark::Close if i > col => "┘",
+                Mark::Close => "└",
+                Mark::Open if i > col => "┐",
+                Mark::Open => "┌",
+                Mark::Join if i > col => "┤",
+                Mark::Join => "├",
+                Mark::None if passing && crossed => "┼",
+                Mark::None if passing => "│",
+                Mark::None if crossed => "─",
+                Mark::None => " ",
+            };
+            row.push_str(glyph);
+            row.push_str(if i >= left && i < right { "─" } else { " " });
+        }
+        rows.push(row);
+
+        for (i, mark) in marks.iter().enumerate() {
+            if *mark == Mark::Close {
+                lanes[i] = None;
+            }
+        }
+        while lanes.last().is_some_and(|l| l.is_none()) {
+            lanes.pop();
+        }
+    }
+    return rows;
+}
@@ -0,0 +1,89 @@
+use crate::theme::Theme;
+use chrono::{Datelike, Duration, NaiveDate};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use std::collections::HashMap;
+
+/// Lines taken by the heatmap: the months, the weekdays and the total
+pub const HEATMAP_HEIGHT: u16 = 9;
+const WEEKS: i64 = 53;
+const LABEL_WIDTH: usize = 4;
+const CELLS: [&str; 5] = ["·", "░", "▒", "▓", "█"];
+
+fn cell(count: i32, max: i32, theme: &Theme) -> Span<'static> {
+    // Levels split the busiest day in quarters like the calendar on GitHub
+    let level = match count {
+        c if c <= 0 || max <= 0 => 0,
+        c => ((c * 4 + max - 1) / max).clamp(1, 4) as usize,
+    };
+    let style: Style = match level {
+        0 => theme.dim,
+        1 => theme.heat_low,
+        2 => theme.heat_mid,
+        3 => theme.heat_high,
+        _ => theme.heat_max,
+    };
+    return Span::styled(CELLS[level], style);
+}
+
+/// Contribution calendar of the year until `today` with a column for each week
+/// starting on Sunday, the oldest weeks are left out when the width is too small
+pub fn heatmap_lines(
+    days: &[(NaiveDate, i32)],
+    today: NaiveDate,
+    width: u16,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let weeks = ((width as usize).saturating_sub(LABEL_WIDTH) as i64).clamp(1, WEEKS);
+    let last_week = today - Duration::days(today.weekday().num_days_from_sunday() as i64);
+    let start = last_week - Duration::weeks(weeks - 1);
+
+    let mut counts: HashMap<NaiveDate, i32> = HashMap::new();
+    for (date, count) in days.iter().filter(|(d, _)| *d >= start && *d <= today) {
+        *counts.entry(*date).or_insert(0) += count;
+    }
+    let max = counts.values().copied().max().unwrap_or(0);
+    let total: i32 = counts.values().sum();
+
+    let mut months = " ".repeat(LABEL_WIDTH);
+    for week in 0..weeks {
+        let week_start = start + Duration::weeks(week);
+        let column = LABEL_WIDTH + week as usize;
+        let new_month =
+            week == 0 || week_start.month() != (week_start - Duration::weeks(1)).month();
+        if new_month && months.chars().count() <= column {
+            months.push_str(&" ".repeat(column - months.chars().count()));
+            months.push_str(&week_start.format("%b").to_string());
+        }
+    }
+    let months: String = months.chars().take(LABEL_WIDTH + weeks as usize).collect();
+
+    let mut lines = vec![Line::styled(months, theme.dim)];
+    for weekday in 0..7 {
+        let label = match weekday {
+            1 => "Mon ",
+            3 => "Wed ",
+            5 => "Fri ",
+            _ => "    ",
+        };
+        let mut spans = vec![Span::styled(label, theme.dim)];
+        for week in 0..weeks {
+            let date = start + Duration::weeks(week) + Duration::days(weekday);
+            if date > today {
+                spans.push(Span::raw(" "));
+            } else {
+                spans.push(cell(counts.get(&date).copied().unwrap_or(0), max, theme));
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+    let period = match weeks {
+        WEEKS => "year".to_string(),
+        w => format!("{} weeks", w),
+    };
+    lines.push(Line::from(vec![
+        Span::styled(total.to_string(), theme.count),
+        Span::raw(format!(" contributions in the last {}", period)),
+    ]));
+    return lines;
+}
@@ -0,0 +1,54 @@
+const MAX_ENTRIES: usize = 100;
+
+/// Back and forward stack of visited states
+pub struct History<T: Clone + PartialEq> {
+    entries: Vec<T>,
+    pos: usize,
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Adds the state after the current one, dropping the forward entries
+    pub fn record(&mut self, state: T) {
+        if self.entries.get(self.pos) == Some(&state) {
+            return;
+        }
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.pos + 1);
+        }
+        self.entries.push(state);
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.pos = self.entries.len() - 1;
+    }
+
+    /// State before the current one, recording the current state first
+    pub fn back(&mut self, current: T) -> Option<T> {
+        self.record(current);
+        if self.pos == 0 {
+            return None;
+        }
+        self.pos -= 1;
+        return self.entries.get(self.pos).cloned();
+    }
+
+    pub fn forward(&mut self, current: T) -> Option<T> {
+        if self.entries.get(self.pos) != Some(&current) {
+            // The view changed since the last step, it becomes the newest entry
+            self.record(current);
+            return None;
+        }
+        if self.pos + 1 >= self.entries.len() {
+            return None;
+        }
+        self.pos += 1;
+        return self.entries.get(self.pos).cloned();
+    }
+}
@@ -158,6 +158,43 @@ fn accept_match(
     return false;
 }
 
+/// Redraws the progress bar of a download on the current line
+pub fn print_progress(done: u64, total: u64) {
+    let width: u64 = 30;
+    let done_shown = match total {
+        0 => width,
+        t => done.min(t) * width / t,
+    };
+    let percent = match total {
+        0 => 100,
+        t => done.min(t) * 100 / t,
+    };
+    let mut cout = std::io::stdout();
+    let _ = write!(
+        &mut cout,
+        "\r[{}{}] {:>3}% {} / {}",
+        "#".repeat(done_shown as usize),
+        " ".repeat((width - done_shown) as usize),
+        percent,
+        crate::git::bytes_string(done),
+        crate::git::bytes_string(total)
+    );
+    let _ = cout.flush();
+}
+
+pub fn wait_for_key(prompt: &str) -> std::io::Result<()> {
+    println!("{}", prompt);
+    crossterm::terminal::enable_raw_mode()?;
+    loop {
+        match crossterm::event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => break,
+            _ => {}
+        }
+    }
+    crossterm::terminal::disable_raw_mode()?;
+    return Ok(());
+}
+
 pub fn clear_below(cout: &mut std::io::Stdout) -> std::io::Result<()> {
     cout.execute(crossterm::terminal::Clear(
         crossterm::terminal::ClearType::FromCursorDown,
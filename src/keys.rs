@@ -0,0 +1,876 @@
+use crate::config::Config;
+use crate::layout::BlockType;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::time::{Duration, Instant};
+
+/// Time to wait for the next key of a sequence before the keys so far run on their own
+const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Quit,
+    MoveUp,
+    MoveDown,
+    MoveTop,
+    MoveBottom,
+    FocusPrevBlock,
+    FocusNextBlock,
+    FocusPrevCol,
+    FocusNextCol,
+    Open,
+    Back,
+    Refresh,
+    OpenBrowser,
+    Copy,
+    CycleSort,
+    CycleGroup,
+    CycleType,
+    CloneRepo,
+    CheckoutCommit,
+    MarkCommit,
+    Compare,
+    FileHistory,
+    Blame,
+    FilterCommits,
+    RerunWorkflow,
+    CancelWorkflow,
+    DispatchWorkflow,
+    SearchLog,
+    NextMatch,
+    PrevMatch,
+    Notifications,
+    MarkRead,
+    MarkDone,
+    Unsubscribe,
+    Activity,
+    Palette,
+    Zoom,
+    GrowWidth,
+    ShrinkWidth,
+    GrowHeight,
+    ShrinkHeight,
+    ResetLayout,
+    HistoryBack,
+    HistoryForward,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    Help,
+}
+
+pub const ACTIONS: [Action; 49] = [
+    Action::Quit,
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::MoveTop,
+    Action::MoveBottom,
+    Action::FocusPrevBlock,
+    Action::FocusNextBlock,
+    Action::FocusPrevCol,
+    Action::FocusNextCol,
+    Action::Open,
+    Action::Back,
+    Action::Refresh,
+    Action::OpenBrowser,
+    Action::Copy,
+    Action::CycleSort,
+    Action::CycleGroup,
+    Action::CycleType,
+    Action::CloneRepo,
+    Action::CheckoutCommit,
+    Action::MarkCommit,
+    Action::Compare,
+    Action::FileHistory,
+    Action::Blame,
+    Action::FilterCommits,
+    Action::RerunWorkflow,
+    Action::CancelWorkflow,
+    Action::DispatchWorkflow,
+    Action::SearchLog,
+    Action::NextMatch,
+    Action::PrevMatch,
+    Action::Notifications,
+    Action::MarkRead,
+    Action::MarkDone,
+    Action::Unsubscribe,
+    Action::Activity,
+    Action::Palette,
+    Action::Zoom,
+    Action::GrowWidth,
+    Action::ShrinkWidth,
+    Action::GrowHeight,
+    Action::ShrinkHeight,
+    Action::ResetLayout,
+    Action::HistoryBack,
+    Action::HistoryForward,
+    Action::NewTab,
+    Action::CloseTab,
+    Action::NextTab,
+    Action::PrevTab,
+    Action::Help,
+];
+
+impl Action {
+    /// Name used for the action in the config file
+    pub fn name(&self) -> &str {
+        match self {
+            Action::Quit => "quit",
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveTop => "move_top",
+            Action::MoveBottom => "move_bottom",
+            Action::FocusPrevBlock => "focus_prev_block",
+            Action::FocusNextBlock => "focus_next_block",
+            Action::FocusPrevCol => "focus_prev_col",
+            Action::FocusNextCol => "focus_next_col",
+            Action::Open => "open",
+            Action::Back => "back",
+            Action::Refresh => "refresh",
+            Action::OpenBrowser => "open_browser",
+            Action::Copy => "copy",
+            Action::CycleSort => "cycle_sort",
+            Action::CycleGroup => "cycle_group",
+            Action::CycleType => "cycle_type",
+            Action::CloneRepo => "clone_repo",
+            Action::CheckoutCommit => "checkout_commit",
+            Action::MarkCommit => "mark_commit",
+            Action::Compare => "compare",
+            Action::FileHistory => "file_history",
+            Action::Blame => "blame",
+            Action::FilterCommits => "filter_commits",
+            Action::RerunWorkflow => "rerun_workflow",
+            Action::CancelWorkflow => "cancel_workflow",
+            Action::DispatchWorkflow => "dispatch_workflow",
+            Action::SearchLog => "search_log",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::Notifications => "notifications",
+            Action::MarkRead => "mark_read",
+            Action::MarkDone => "mark_done",
+            Action::Unsubscribe => "unsubscribe",
+            Action::Activity => "activity",
+            Action::Palette => "palette",
+            Action::Zoom => "zoom",
+            Action::GrowWidth => "grow_width",
+            Action::ShrinkWidth => "shrink_width",
+            Action::GrowHeight => "grow_height",
+            Action::ShrinkHeight => "shrink_height",
+            Action::ResetLayout => "reset_layout",
+            Action::HistoryBack => "history_back",
+            Action::HistoryForward => "history_forward",
+            Action::NewTab => "new_tab",
+            Action::CloseTab => "close_tab",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::Help => "help",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        return ACTIONS.iter().find(|a| a.name() == name).copied();
+    }
+
+    pub fn description(&self) -> &str {
+        match self {
+            Action::Quit => "Quit",
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveTop => "Move to the top",
+            Action::MoveBottom => "Move to the bottom",
+            Action::FocusPrevBlock => "Focus previous block",
+            Action::FocusNextBlock => "Focus next block",
+            Action::FocusPrevCol => "Focus previous column",
+            Action::FocusNextCol => "Focus next column",
+            Action::Open => "Open selection",
+            Action::Back => "Go back",
+            Action::Refresh => "Refresh",
+            Action::OpenBrowser => "Open in browser",
+            Action::Copy => "Copy to clipboard",
+            Action::CycleSort => "Cycle repo sort",
+            Action::CycleGroup => "Cycle repo grouping",
+            Action::CycleType => "Cycle repo or search type",
+            Action::CloneRepo => "Clone selected repo",
+            Action::CheckoutCommit => "Checkout this commit locally",
+            Action::MarkCommit => "Mark commit as compare base",
+            Action::Compare => "Compare commits or refs",
+            Action::FileHistory => "Show history of file",
+            Action::Blame => "Blame file",
+            Action::FilterCommits => "Filter commits",
+            Action::RerunWorkflow => "Rerun workflow run",
+            Action::CancelWorkflow => "Cancel workflow run",
+            Action::DispatchWorkflow => "Run workflow with inputs",
+            Action::SearchLog => "Search job log",
+            Action::NextMatch => "Next match in job log",
+            Action::PrevMatch => "Previous match in job log",
+            Action::Notifications => "Show notifications",
+            Action::MarkRead => "Mark notification read",
+            Action::MarkDone => "Mark notification done",
+            Action::Unsubscribe => "Unsubscribe from notification thread",
+            Action::Activity => "Show activity feed of the user",
+            Action::Palette => "Command palette",
+            Action::Zoom => "Zoom focused block",
+            Action::GrowWidth => "Grow width",
+            Action::ShrinkWidth => "Shrink width",
+            Action::GrowHeight => "Grow height",
+            Action::ShrinkHeight => "Shrink height",
+            Action::ResetLayout => "Reset panel sizes",
+            Action::HistoryBack => "Back in history",
+            Action::HistoryForward => "Forward in history",
+            Action::NewTab => "Open a new tab",
+            Action::CloseTab => "Close tab",
+            Action::NextTab => "Next tab",
+            Action::PrevTab => "Previous tab",
+            Action::Help => "Show key bindings",
+        }
+    }
+
+    /// Description of what the action does in the block
+    pub fn label(&self, block_type: &BlockType) -> String {
+        let target = match block_type {
+            BlockType::Profile => "profile",
+            BlockType::Repos | BlockType::Info => "repo",
+            BlockType::Commits => "commit",
+            BlockType::CommitInfo => "file",
+            BlockType::Actions => "run",
+            BlockType::Releases => "release",
+            _ => "",
+        };
+        return match self {
+            Action::Refresh => match block_type {
+                BlockType::Profile => "Refresh rate limit".to_string(),
+                BlockType::Repos => "Refresh repos".to_string(),
+                BlockType::Info => "Refresh repo info".to_string(),
+                BlockType::Commits => "Refresh commits".to_string(),
+                BlockType::CommitInfo => "Refresh commit info".to_string(),
+                BlockType::Actions => "Refresh workflow runs".to_string(),
+                BlockType::Releases => "Refresh releases".to_string(),
+                _ => self.description().to_string(),
+            },
+            Action::Open => match block_type {
+                BlockType::Profile => "Open selected org or member".to_string(),
+                BlockType::Repos => "Show commits of selected repo".to_string(),
+                BlockType::Commits => "Show commit info".to_string(),
+                BlockType::CommitInfo => "Show diff of selected file".to_string(),
+                BlockType::Actions => {
+                    "Show jobs of selected run or log of selected job".to_string()
+                }
+                BlockType::Releases => "Show notes and assets of selected release".to_string(),
+                _ => self.description().to_string(),
+            },
+            Action::OpenBrowser if !target.is_empty() => format!("Open {} in browser", target),
+            Action::Copy if !target.is_empty() => format!("Copy {} to clipboard", target),
+            Action::CycleType if *block_type == BlockType::SearchGlobal => {
+                "Cycle search type".to_string()
+            }
+            Action::CycleType => "Cycle repo type".to_string(),
+            _ => self.description().to_string(),
+        };
+    }
+
+    /// Whether the action does something when the block is focused
+    pub fn applies_to(&self, block_type: &BlockType) -> bool {
+        match self {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                matches!(
+                    block_type,
+                    BlockType::Profile
+                        | BlockType::Repos
+                        | BlockType::Commits
+                        | BlockType::CommitInfo
+                        | BlockType::Actions
+                        | BlockType::Releases
+                )
+            }
+            Action::Refresh | Action::OpenBrowser | Action::Copy => matches!(
+                block_type,
+                BlockType::Profile
+                    | BlockType::Repos
+                    | BlockType::Info
+                    | BlockType::Commits
+                    | BlockType::CommitInfo
+                    | BlockType::Actions
+                    | BlockType::Releases
+            ),
+            Action::CycleSort | Action::CycleGroup => *block_type == BlockType::Repos,
+            Action::CloneRepo => matches!(
+                block_type,
+                BlockType::Repos | BlockType::Info | BlockType::Commits | BlockType::CommitInfo
+            ),
+            Action::CheckoutCommit => {
+                matches!(block_type, BlockType::Commits | BlockType::CommitInfo)
+            }
+            Action::CycleType => {
+                matches!(block_type, BlockType::Repos | BlockType::SearchGlobal)
+            }
+            Action::MarkCommit | Action::FilterCommits => *block_type == BlockType::Commits,
+            Action::FileHistory | Action::Blame => *block_type == BlockType::CommitInfo,
+            Action::RerunWorkflow | Action::CancelWorkflow | Action::DispatchWorkflow => {
+                *block_type == BlockType::Actions
+            }
+            // The job log opens from the actions block
+            Action::SearchLog | Action::NextMatch | Action::PrevMatch => {
+                *block_type == BlockType::Actions
+            }
+            // Only in the notifications inbox
+            Action::MarkRead | Action::MarkDone | Action::Unsubscribe => false,
+            Action::Compare => matches!(
+                block_type,
+                BlockType::Repos | BlockType::Info | BlockType::Commits
+            ),
+            _ => true,
+        }
+    }
+
+    /// Actions only bound in the job log, where their keys take over the ones of other actions
+    pub fn in_log(&self) -> bool {
+        return matches!(
+            self,
+            Action::SearchLog | Action::NextMatch | Action::PrevMatch
+        );
+    }
+
+    fn default_keys(&self) -> &str {
+        match self {
+            Action::Quit => "q, ctrl+c",
+            Action::MoveUp => "k, Up",
+            Action::MoveDown => "j, Down",
+            Action::MoveTop => "gg, Home",
+            Action::MoveBottom => "G, End",
+            Action::FocusPrevBlock => "h, Left",
+            Action::FocusNextBlock => "l, Right",
+            Action::FocusPrevCol => "H",
+            Action::FocusNextCol => "L",
+            Action::Open => "Enter, Tab",
+            Action::Back => "Esc",
+            Action::Refresh => "r",
+            Action::OpenBrowser => "o",
+            Action::Copy => "y",
+            Action::CycleSort => "s, ctrl+s",
+            Action::CycleGroup => "ctrl+g",
+            Action::CycleType => "t",
+            Action::CloneRepo => "c",
+            Action::CheckoutCommit => "C",
+            Action::MarkCommit => "m",
+            Action::Compare => "d",
+            Action::FileHistory => "f",
+            Action::Blame => "b",
+            Action::FilterCommits => "/",
+            Action::RerunWorkflow => "R",
+            Action::CancelWorkflow => "X",
+            Action::DispatchWorkflow => "W",
+            Action::SearchLog => "/",
+            Action::NextMatch => "n",
+            Action::PrevMatch => "N",
+            Action::Notifications => "i",
+            Action::MarkRead => "M",
+            Action::MarkDone => "D",
+            Action::Unsubscribe => "U",
+            Action::Activity => "a",
+            Action::Palette => ":, ctrl+p",
+            Action::Zoom => "z",
+            Action::GrowWidth => ">",
+            Action::ShrinkWidth => "<",
+            Action::GrowHeight => "+",
+            Action::ShrinkHeight => "-",
+            Action::ResetLayout => "=",
+            Action::HistoryBack => "ctrl+o, alt+Left",
+            Action::HistoryForward => "ctrl+f, alt+Right",
+            Action::NewTab => "ctrl+t",
+            Action::CloseTab => "ctrl+w",
+            Action::NextTab => "gt, ]",
+            Action::PrevTab => "gT, [",
+            Action::Help => "?",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // Shift is already part of an uppercase char
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        return KeyChord::new(key.code, key.modifiers);
+    }
+
+    /// Plain characters that are typed into inputs
+    pub fn is_text(&self) -> bool {
+        return matches!(self.code, KeyCode::Char(_))
+            && !self
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+    }
+
+    fn parse(chord: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = chord;
+        loop {
+            let lower = key.to_lowercase();
+            if lower.starts_with("ctrl+") {
+                modifiers |= KeyModifiers::CONTROL;
+            } else if lower.starts_with("alt+") {
+                modifiers |= KeyModifiers::ALT;
+            } else if lower.starts_with("shift+") {
+                modifiers |= KeyModifiers::SHIFT;
+            } else {
+                break;
+            }
+            key = &key[key.find('+')? + 1..];
+        }
+        let code = match key.to_lowercase().as_str() {
+            "enter" => KeyCode::Enter,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "esc" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "comma" => KeyCode::Char(','),
+            k if k.len() > 1 && k.starts_with('f') => KeyCode::F(k[1..].parse().ok()?),
+            _ if key.chars().count() == 1 => KeyCode::Char(key.chars().next()?),
+            _ => return None,
+        };
+        if modifiers == KeyModifiers::SHIFT && code == KeyCode::Tab {
+            return Some(KeyChord::new(KeyCode::BackTab, KeyModifiers::NONE));
+        }
+        return Some(KeyChord::new(code, modifiers));
+    }
+
+    pub fn to_string(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            code => format!("{:?}", code),
+        };
+        let mut chord = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            chord.push_str("ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            chord.push_str("alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            chord.push_str("shift+");
+        }
+        chord.push_str(&key);
+        return chord;
+    }
+}
+
+/// Parses a key sequence, either space separated chords like `ctrl+x ctrl+s`
+/// or a run of plain characters like `gg`
+fn parse_sequence(spec: &str) -> Option<Vec<KeyChord>> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return None;
+    }
+    if let Some(chord) = KeyChord::parse(spec) {
+        return Some(vec![chord]);
+    }
+    if spec.contains(' ') {
+        return spec.split_whitespace().map(KeyChord::parse).collect();
+    }
+    if spec.chars().all(|c| !c.is_whitespace()) && !spec.contains('+') {
+        return Some(
+            spec.chars()
+                .map(|c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE))
+                .collect(),
+        );
+    }
+    return None;
+}
+
+fn sequence_to_string(keys: &Vec<KeyChord>) -> String {
+    if keys.iter().all(|k| k.is_text()) {
+        return keys.iter().map(|k| k.to_string()).collect();
+    }
+    return keys
+        .iter()
+        .map(|k| k.to_string())
+        .collect::<Vec<String>>()
+        .join(" ");
+}
+
+pub struct Binding {
+    pub keys: Vec<KeyChord>,
+    pub action: Action,
+}
+
+pub enum KeyResult {
+    Action(Action),
+    Pending,
+    Unbound,
+}
+
+pub struct KeyMap {
+    bindings: Vec<Binding>,
+    pending: Vec<KeyChord>,
+    pending_at: Option<Instant>,
+    resolve: bool, // The next key ends the sequence even if a longer one could follow
+    pub conflicts: Vec<String>,
+}
+
+impl KeyMap {
+    /// Default bindings, with the actions set as `bind.<action>=<keys>` in
+    /// the config replacing the defaults of that action. Keys set in the
+    /// config are removed from the defaults of the other actions of the
+    /// same view.
+    pub fn new(config: &Option<Config>) -> Self {
+        let mut keymap = Self {
+            bindings: Vec::new(),
+            pending: Vec::new(),
+            pending_at: None,
+            resolve: false,
+            conflicts: Vec::new(),
+        };
+        for action in ACTIONS.iter() {
+            keymap.bind(*action, action.default_keys());
+        }
+        if let Some(c) = config {
+            for (name, keys) in c.keybindings.iter() {
+                if let Some(action) = Action::from_name(name) {
+                    keymap.bindings.retain(|b| b.action != action);
+                    keymap.bind(action, keys);
+                }
+            }
+            let configured: Vec<Action> = c
+                .keybindings
+                .iter()
+                .filter_map(|(name, _)| Action::from_name(name))
+                .collect();
+            let taken: Vec<(Vec<KeyChord>, bool)> = keymap
+                .bindings
+                .iter()
+                .filter(|b| configured.contains(&b.action))
+                .map(|b| (b.keys.clone(), b.action.in_log()))
+                .collect();
+            keymap.bindings.retain(|b| {
+                configured.contains(&b.action)
+                    || !taken.contains(&(b.keys.clone(), b.action.in_log()))
+            });
+        }
+        keymap.conflicts = keymap.find_conflicts();
+        return keymap;
+    }
+
+    /// Key sequences bound to more than one action of the same view, only the first of them runs
+    fn find_conflicts(&self) -> Vec<String> {
+        let mut conflicts: Vec<String> = Vec::new();
+        for (i, b) in self.bindings.iter().enumerate() {
+            let earlier = self.bindings[..i].iter().find(|o| {
+                o.keys == b.keys && o.action != b.action && o.action.in_log() == b.action.in_log()
+            });
+            if let Some(o) = earlier {
+                conflicts.push(format!(
+                    "{} is bound to both {} and {}",
+                    sequence_to_string(&b.keys),
+                    o.action.name(),
+                    b.action.name()
+                ));
+            }
+        }
+        return conflicts;
+    }
+
+    fn bind(&mut self, action: Action, keys: &str) {
+        for spec in keys.split(',') {
+            if let Some(sequence) = parse_sequence(spec) {
+                self.bindings.push(Binding {
+                    keys: sequence,
+                    action,
+                });
+            }
+        }
+    }
+
+    pub fn pending(&self) -> String {
+        return sequence_to_string(&self.pending);
+    }
+
+    /// Time left before the pending keys time out, None when no keys are pending
+    pub fn pending_timeout(&self) -> Option<Duration> {
+        return self
+            .pending_at
+            .map(|t| SEQUENCE_TIMEOUT.saturating_sub(t.elapsed()));
+    }
+
+    /// Ends the pending sequence when no key followed in time. Gives the last
+    /// key to feed again when the keys so far are bound on their own.
+    pub fn expire(&mut self) -> Option<KeyEvent> {
+        self.pending_at = None;
+        if !self.bindings.iter().any(|b| b.keys == self.pending) {
+            self.pending.clear();
+            return None;
+        }
+        let last = self.pending.pop()?;
+        self.resolve = true;
+        return Some(KeyEvent::new(last.code, last.modifiers));
+    }
+
+    /// Feeds a key press, waiting for more keys while it starts a longer sequence
+    pub fn handle(&mut self, key: &KeyEvent) -> KeyResult {
+        return self.handle_in(key, false);
+    }
+
+    /// Feeds a key press in the job log, where the log actions come before the others
+    pub fn handle_log(&mut self, key: &KeyEvent) -> KeyResult {
+        return self.handle_in(key, true);
+    }
+
+    /// Bindings that can run in the job log or elsewhere
+    fn bindings_in(&self, log: bool) -> Vec<&Binding> {
+        return self
+            .bindings
+            .iter()
+            .filter(|b| match log {
+                true => {
+                    b.action.in_log()
+                        || !self
+                            .bindings
+                            .iter()
+                            .any(|o| o.action.in_log() && o.keys == b.keys)
+                }
+                false => !b.action.in_log(),
+            })
+            .collect();
+    }
+
+    fn handle_in(&mut self, key: &KeyEvent, log: bool) -> KeyResult {
+        let chord = KeyChord::from_event(key);
+        let mut sequence = self.pending.clone();
+        sequence.push(chord);
+        let bindings = self.bindings_in(log);
+        let is_prefix = !self.resolve
+            && bindings
+                .iter()
+                .any(|b| b.keys.len() > sequence.len() && b.keys.starts_with(&sequence));
+        let bound = bindings
+            .iter()
+            .find(|b| b.keys == sequence)
+            .map(|b| b.action);
+        self.resolve = false;
+        if is_prefix {
+            self.pending = sequence;
+            self.pending_at = Some(Instant::now());
+            return KeyResult::Pending;
+        }
+        self.pending.clear();
+        self.pending_at = None;
+        if let Some(action) = bound {
+            return KeyResult::Action(action);
+        }
+        if sequence.len() > 1 {
+            // The sequence broke off, try the last key on its own
+            return self.handle_in(key, log);
+        }
+        return KeyResult::Unbound;
+    }
+
+    /// All key sequences of the action, comma separated
+    pub fn keys_for(&self, action: Action) -> String {
+        return self
+            .bindings
+            .iter()
+            .filter(|b| b.action == action)
+            .map(|b| sequence_to_string(&b.keys))
+            .collect::<Vec<String>>()
+            .join(", ");
+    }
+
+    /// Bound actions that apply to the block, with their keys
+    pub fn help_lines(&self, block_type: &BlockType) -> Vec<(String, String)> {
+        return ACTIONS
+            .iter()
+            .filter(|a| a.applies_to(block_type))
+            .map(|a| (self.keys_for(*a), a.description().to_string()))
+            .filter(|(keys, _)| !keys.is_empty())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> KeyEvent {
+        return KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        return KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+    }
+
+    fn keymap_with(bindings: &[(&str, &str)]) -> KeyMap {
+        let mut config = Config::new(None, None);
+        config.keybindings = bindings
+            .iter()
+            .map(|(a, k)| (a.to_string(), k.to_string()))
+            .collect();
+        return KeyMap::new(&Some(config));
+    }
+
+    fn action(result: KeyResult) -> Option<Action> {
+        return match result {
+            KeyResult::Action(action) => Some(action),
+            _ => None,
+        };
+    }
+
+    #[test]
+    fn single_keys() {
+        let mut keymap = KeyMap::new(&None);
+        assert_eq!(action(keymap.handle(&key('j'))), Some(Action::MoveDown));
+        assert_eq!(action(keymap.handle(&ctrl('p'))), Some(Action::Palette));
+        assert_eq!(
+            action(keymap.handle(&KeyEvent::new(KeyCode::Up, KeyModifiers::NONE))),
+            Some(Action::MoveUp)
+        );
+    }
+
+    #[test]
+    fn shift_is_part_of_uppercase_chars() {
+        let mut keymap = KeyMap::new(&None);
+        let shifted = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(action(keymap.handle(&shifted)), Some(Action::MoveBottom));
+        assert_eq!(action(keymap.handle(&key('G'))), Some(Action::MoveBottom));
+    }
+
+    #[test]
+    fn unbound_key() {
+        let mut keymap = KeyMap::new(&None);
+        let key = KeyEvent::new(KeyCode::F(12), KeyModifiers::NONE);
+        assert!(matches!(keymap.handle(&key), KeyResult::Unbound));
+        assert_eq!(keymap.pending_timeout(), None);
+    }
+
+    #[test]
+    fn sequence_waits_for_next_key() {
+        let mut keymap = KeyMap::new(&None);
+        assert!(matches!(keymap.handle(&key('g')), KeyResult::Pending));
+        assert_eq!(keymap.pending(), "g");
+        assert!(keymap.pending_timeout().is_some());
+        assert_eq!(action(keymap.handle(&key('g'))), Some(Action::MoveTop));
+        assert_eq!(keymap.pending(), "");
+        assert_eq!(keymap.pending_timeout(), None);
+
+        assert!(matches!(keymap.handle(&key('g')), KeyResult::Pending));
+        assert_eq!(action(keymap.handle(&key('T'))), Some(Action::PrevTab));
+    }
+
+    #[test]
+    fn broken_sequence_runs_last_key() {
+        let mut keymap = KeyMap::new(&None);
+        keymap.handle(&key('g'));
+        assert_eq!(action(keymap.handle(&key('j'))), Some(Action::MoveDown));
+        assert_eq!(keymap.pending(), "");
+    }
+
+    #[test]
+    fn expired_prefix_without_binding_is_dropped() {
+        let mut keymap = KeyMap::new(&None);
+        keymap.handle(&key('g'));
+        assert!(keymap.expire().is_none());
+        assert_eq!(keymap.pending(), "");
+        assert_eq!(action(keymap.handle(&key('g'))), None);
+    }
+
+    #[test]
+    fn prefix_bound_on_its_own_runs_after_timeout() {
+        let mut keymap = keymap_with(&[("zoom", "g")]);
+        assert!(matches!(keymap.handle(&key('g')), KeyResult::Pending));
+        let replayed = keymap.expire().expect("g is bound");
+        assert_eq!(action(keymap.handle(&replayed)), Some(Action::Zoom));
+        assert_eq!(keymap.pending(), "");
+        // The longer sequence still works
+        keymap.handle(&key('g'));
+        assert_eq!(action(keymap.handle(&key('g'))), Some(Action::MoveTop));
+    }
+
+    #[test]
+    fn chord_sequences() {
+        let mut keymap = keymap_with(&[("zoom", "ctrl+x z")]);
+        assert!(matches!(keymap.handle(&ctrl('x')), KeyResult::Pending));
+        assert_eq!(keymap.pending(), "ctrl+x");
+        assert_eq!(action(keymap.handle(&key('z'))), Some(Action::Zoom));
+        // The config replaces the default `z`
+        assert!(matches!(keymap.handle(&key('z')), KeyResult::Unbound));
+    }
+
+    #[test]
+    fn config_keys_are_taken_from_other_actions() {
+        let mut keymap = keymap_with(&[("move_up", "k, Up, ctrl+p")]);
+        assert!(keymap.conflicts.is_empty());
+        assert_eq!(action(keymap.handle(&ctrl('p'))), Some(Action::MoveUp));
+        assert_eq!(action(keymap.handle(&key(':'))), Some(Action::Palette));
+        assert_eq!(keymap.keys_for(Action::Palette), ":");
+    }
+
+    #[test]
+    fn default_keys_do_not_conflict() {
+        assert!(KeyMap::new(&None).conflicts.is_empty());
+    }
+
+    #[test]
+    fn log_keys_only_apply_in_the_log() {
+        let mut keymap = KeyMap::new(&None);
+        assert_eq!(
+            action(keymap.handle(&key('/'))),
+            Some(Action::FilterCommits)
+        );
+        assert_eq!(action(keymap.handle(&key('n'))), None);
+        assert_eq!(
+            action(keymap.handle_log(&key('/'))),
+            Some(Action::SearchLog)
+        );
+        assert_eq!(
+            action(keymap.handle_log(&key('N'))),
+            Some(Action::PrevMatch)
+        );
+        assert_eq!(action(keymap.handle_log(&key('j'))), Some(Action::MoveDown));
+    }
+
+    #[test]
+    fn log_config_keys_are_not_taken_from_other_views() {
+        let mut keymap = keymap_with(&[("search_log", "f")]);
+        assert!(keymap.conflicts.is_empty());
+        assert_eq!(action(keymap.handle(&key('f'))), Some(Action::FileHistory));
+        assert_eq!(
+            action(keymap.handle_log(&key('f'))),
+            Some(Action::SearchLog)
+        );
+        assert_eq!(
+            action(keymap.handle_log(&key('/'))),
+            Some(Action::FilterCommits)
+        );
+    }
+
+    #[test]
+    fn conflicting_config_bindings_are_reported() {
+        let mut keymap = keymap_with(&[("zoom", "ctrl+k"), ("help", "ctrl+k")]);
+        assert_eq!(
+            keymap.conflicts,
+            vec!["ctrl+k is bound to both zoom and help".to_string()]
+        );
+        assert_eq!(action(keymap.handle(&ctrl('k'))), Some(Action::Zoom));
+    }
+}
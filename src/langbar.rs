@@ -0,0 +1,104 @@
+use crate::theme::Theme;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+
+// Told apart by the pattern when colours are turned off
+const PATTERNS: [&str; 4] = ["█", "▓", "▒", "░"];
+const OTHER_COLORS: [Color; 6] = [
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Red,
+    Color::Green,
+];
+
+/// Colour of the language on GitHub, or one of the basic colours for the rest
+fn language_color(language: &str, index: usize) -> Color {
+    let rgb = match language {
+        "Rust" => (222, 165, 132),
+        "Python" => (53, 114, 165),
+        "JavaScript" => (241, 224, 90),
+        "TypeScript" => (49, 120, 198),
+        "Go" => (0, 173, 216),
+        "C" => (85, 85, 85),
+        "C++" => (243, 75, 125),
+        "C#" => (23, 134, 0),
+        "Java" => (176, 114, 25),
+        "Kotlin" => (169, 123, 255),
+        "Swift" => (240, 81, 56),
+        "Ruby" => (112, 21, 22),
+        "PHP" => (79, 93, 149),
+        "Shell" => (137, 224, 81),
+        "HTML" => (227, 76, 38),
+        "CSS" => (86, 61, 124),
+        "Lua" => (0, 0, 128),
+        "Zig" => (236, 145, 92),
+        "Nix" => (126, 126, 255),
+        "Haskell" => (94, 80, 134),
+        "Makefile" => (66, 120, 25),
+        "CMake" => (218, 52, 52),
+        "Dockerfile" => (56, 77, 84),
+        "Vim Script" => (25, 159, 75),
+        _ => return OTHER_COLORS[index % OTHER_COLORS.len()],
+    };
+    return Color::Rgb(rgb.0, rgb.1, rgb.2);
+}
+
+fn language_span(language: &str, index: usize, text: String, theme: &Theme) -> Span<'static> {
+    return match theme.colors {
+        true => Span::styled(text, Style::new().fg(language_color(language, index))),
+        false => Span::raw(text),
+    };
+}
+
+fn pattern(index: usize, theme: &Theme) -> &'static str {
+    return match theme.colors {
+        true => PATTERNS[0],
+        false => PATTERNS[index % PATTERNS.len()],
+    };
+}
+
+/// Bar of the share of each language and the legend with the percentages
+pub fn language_lines(
+    languages: &[(String, i64)],
+    width: u16,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let total: i64 = languages.iter().map(|(_, bytes)| bytes).sum();
+    if total <= 0 || width == 0 {
+        return Vec::new();
+    }
+    let mut bar: Vec<Span> = Vec::new();
+    let mut sum: i64 = 0;
+    let mut end: usize = 0;
+    for (i, (language, bytes)) in languages.iter().enumerate() {
+        sum += bytes;
+        // Rounding the running total keeps the bar exactly as wide as the block
+        let next_end = ((sum as f64 / total as f64) * width as f64).round() as usize;
+        if next_end > end {
+            let cells = pattern(i, theme).repeat(next_end - end);
+            bar.push(language_span(language, i, cells, theme));
+        }
+        end = next_end;
+    }
+
+    let mut legend: Vec<Span> = Vec::new();
+    for (i, (language, bytes)) in languages.iter().enumerate() {
+        if i > 0 {
+            legend.push(Span::raw("  "));
+        }
+        legend.push(language_span(
+            language,
+            i,
+            pattern(i, theme).to_string(),
+            theme,
+        ));
+        legend.push(Span::raw(format!(
+            " {} {:.1}%",
+            language,
+            *bytes as f64 * 100.0 / total as f64
+        )));
+    }
+    return vec![Line::from(bar), Line::from(legend)];
+}
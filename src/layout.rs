@@ -1,4 +1,10 @@
-#[derive(PartialEq, Clone)]
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+pub const DEFAULT_LAYOUT: &str =
+    "row(col[1fr](profile[auto], repos[min0], search[11], status[auto]), \
+    col[2fr](info[10], commits[min10], commit_info[min10], row[min6](actions, releases)))";
+
+#[derive(PartialEq, Clone, Debug)]
 pub enum BlockType {
     Profile,
     Repos,
@@ -8,9 +14,53 @@ pub enum BlockType {
     CommitInfo,
     SearchUser,
     SearchRepo,
+    SearchGlobal,
+    Status,
+    Actions,
+    Releases,
     Default,
 }
 
+impl BlockType {
+    pub fn from_name(name: &str) -> Option<BlockType> {
+        return match name {
+            "profile" => Some(BlockType::Profile),
+            "repos" => Some(BlockType::Repos),
+            "search" => Some(BlockType::Search),
+            "info" => Some(BlockType::Info),
+            "commits" => Some(BlockType::Commits),
+            "commit_info" => Some(BlockType::CommitInfo),
+            "status" => Some(BlockType::Status),
+            "actions" => Some(BlockType::Actions),
+            "releases" => Some(BlockType::Releases),
+            _ => None,
+        };
+    }
+
+    pub fn name(&self) -> &str {
+        return match self {
+            BlockType::Profile => "profile",
+            BlockType::Repos => "repos",
+            BlockType::Search => "search",
+            BlockType::Info => "info",
+            BlockType::Commits => "commits",
+            BlockType::CommitInfo => "commit_info",
+            BlockType::Status => "status",
+            BlockType::Actions => "actions",
+            BlockType::Releases => "releases",
+            BlockType::SearchUser => "search_user",
+            BlockType::SearchRepo => "search_repo",
+            BlockType::SearchGlobal => "search_global",
+            BlockType::Default => "default",
+        };
+    }
+
+    /// Blocks that can't be focused are only drawn
+    pub fn is_focusable(&self) -> bool {
+        return !matches!(self, BlockType::Status | BlockType::Default);
+    }
+}
+
 #[derive(PartialEq, Clone)]
 pub enum BlockState {
     Default,
@@ -26,6 +76,10 @@ pub fn block_type(b_i: u8) -> BlockType {
         5 => BlockType::CommitInfo,
         6 => BlockType::SearchUser,
         7 => BlockType::SearchRepo,
+        8 => BlockType::SearchGlobal,
+        9 => BlockType::Status,
+        10 => BlockType::Actions,
+        11 => BlockType::Releases,
         _ => BlockType::Default,
     }
 }
@@ -34,6 +88,308 @@ pub fn block_type_to_u8(block_type: BlockType) -> u8 {
     return block_type as u8;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Size {
+    Length(u16),
+    Percent(u16),
+    Fill(u16),
+    Min(u16),
+    Auto, // Height or width of the block content
+}
+
+impl Size {
+    fn parse(size: &str) -> Result<Size, String> {
+        let size = size.trim();
+        let number = |n: &str| {
+            n.parse::<u16>()
+                .map_err(|_| format!("invalid size '{}'", size))
+        };
+        return match size {
+            "auto" => Ok(Size::Auto),
+            s if s.ends_with('%') => Ok(Size::Percent(number(&s[..s.len() - 1])?)),
+            s if s.ends_with("fr") => Ok(Size::Fill(number(&s[..s.len() - 2])?)),
+            s if s.starts_with("min") => Ok(Size::Min(number(&s[3..])?)),
+            s => Ok(Size::Length(number(s)?)),
+        };
+    }
+
+    fn to_string(&self) -> String {
+        return match *self {
+            Size::Length(n) => n.to_string(),
+            Size::Percent(n) => format!("{}%", n),
+            Size::Fill(n) => format!("{}fr", n),
+            Size::Min(n) => format!("min{}", n),
+            Size::Auto => "auto".to_string(),
+        };
+    }
+
+    /// Size one step larger or smaller, None when it can't be resized
+    fn resized(&self, grow: bool) -> Option<Size> {
+        let step = |n: u16, by: u16| match grow {
+            true => n.saturating_add(by),
+            false => n.saturating_sub(by).max(1),
+        };
+        return match *self {
+            Size::Length(n) => Some(Size::Length(step(n, 2))),
+            Size::Percent(n) => Some(Size::Percent(step(n, 5).clamp(5, 95))),
+            Size::Fill(n) => Some(Size::Fill(step(n, 1))),
+            Size::Min(n) => Some(Size::Min(step(n, 2))),
+            Size::Auto => None,
+        };
+    }
+
+    fn constraint(&self, auto: u16) -> Constraint {
+        return match *self {
+            Size::Length(n) => Constraint::Length(n),
+            Size::Percent(n) => Constraint::Percentage(n),
+            Size::Fill(n) => Constraint::Fill(n),
+            Size::Min(n) => Constraint::Min(n),
+            Size::Auto => Constraint::Length(auto),
+        };
+    }
+}
+
+/// Layout tree, rows place their children side by side and cols stack them
+#[derive(Debug, Clone)]
+pub enum LayoutNode {
+    Block(BlockType, Size),
+    Row(Size, Vec<LayoutNode>),
+    Col(Size, Vec<LayoutNode>),
+}
+
+impl LayoutNode {
+    /// Parses a tree like `row(col[30%](profile[auto], repos), commits[2fr])`,
+    /// sizes are `10`, `30%`, `2fr`, `min10` or `auto` and default to `1fr`
+    pub fn parse(spec: &str) -> Result<LayoutNode, String> {
+        let chars: Vec<char> = spec.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut pos = 0;
+        let node = LayoutNode::parse_node(&chars, &mut pos)?;
+        if pos < chars.len() {
+            return Err(format!("unexpected '{}'", chars[pos]));
+        }
+        return Ok(node);
+    }
+
+    fn parse_node(chars: &[char], pos: &mut usize) -> Result<LayoutNode, String> {
+        let start = *pos;
+        while *pos < chars.len() && (chars[*pos].is_alphanumeric() || chars[*pos] == '_') {
+            *pos += 1;
+        }
+        let name: String = chars[start..*pos].iter().collect();
+        let mut size = Size::Fill(1);
+        if chars.get(*pos) == Some(&'[') {
+            let end = chars[*pos..]
+                .iter()
+                .position(|c| *c == ']')
+                .ok_or("missing ']'")?;
+            size = Size::parse(&chars[*pos + 1..*pos + end].iter().collect::<String>())?;
+            *pos += end + 1;
+        }
+        if name != "row" && name != "col" {
+            return match BlockType::from_name(&name) {
+                Some(block_type) => Ok(LayoutNode::Block(block_type, size)),
+                None => Err(format!("unknown block '{}'", name)),
+            };
+        }
+        if chars.get(*pos) != Some(&'(') {
+            return Err(format!("missing '(' after '{}'", name));
+        }
+        *pos += 1;
+        let mut children: Vec<LayoutNode> = Vec::new();
+        loop {
+            children.push(LayoutNode::parse_node(chars, pos)?);
+            match chars.get(*pos) {
+                Some(',') => *pos += 1,
+                Some(')') => {
+                    *pos += 1;
+                    break;
+                }
+                _ => return Err(format!("missing ')' in '{}'", name)),
+            }
+        }
+        return Ok(match name.as_str() {
+            "row" => LayoutNode::Row(size, children),
+            _ => LayoutNode::Col(size, children),
+        });
+    }
+
+    pub fn to_string(&self) -> String {
+        let (name, size, children) = match self {
+            LayoutNode::Block(block_type, size) => (block_type.name(), size, None),
+            LayoutNode::Row(size, children) => ("row", size, Some(children)),
+            LayoutNode::Col(size, children) => ("col", size, Some(children)),
+        };
+        let mut node = format!("{}[{}]", name, size.to_string());
+        if let Some(c) = children {
+            let children: Vec<String> = c.iter().map(|c| c.to_string()).collect();
+            node.push_str(&format!("({})", children.join(", ")));
+        }
+        return node;
+    }
+
+    fn contains(&self, block_type: &BlockType) -> bool {
+        return match self {
+            LayoutNode::Block(b, _) => b == block_type,
+            LayoutNode::Row(_, children) | LayoutNode::Col(_, children) => {
+                children.iter().any(|c| c.contains(block_type))
+            }
+        };
+    }
+
+    fn set_size(&mut self, new_size: Size) {
+        match self {
+            LayoutNode::Block(_, size) | LayoutNode::Row(size, _) | LayoutNode::Col(size, _) => {
+                *size = new_size
+            }
+        }
+    }
+
+    /// Grows or shrinks the nearest node holding the block that is split in the
+    /// direction, Some(false) while no node on the path could be resized yet
+    fn resize(&mut self, block_type: &BlockType, direction: Direction, grow: bool) -> Option<bool> {
+        let (children, split_direction) = match self {
+            LayoutNode::Block(b, _) => return (b == block_type).then_some(false),
+            LayoutNode::Row(_, children) => (children, Direction::Horizontal),
+            LayoutNode::Col(_, children) => (children, Direction::Vertical),
+        };
+        let i = children.iter().position(|c| c.contains(block_type))?;
+        if children[i].resize(block_type, direction, grow)? {
+            return Some(true);
+        }
+        if split_direction != direction {
+            return Some(false);
+        }
+        // Finer steps for fill weights
+        if let Size::Fill(w) = children[i].size() {
+            if w < 10 {
+                for c in children.iter_mut() {
+                    if let Size::Fill(w) = c.size() {
+                        c.set_size(Size::Fill(w * 10));
+                    }
+                }
+            }
+        }
+        return match children[i].size().resized(grow) {
+            Some(size) => {
+                children[i].set_size(size);
+                Some(true)
+            }
+            None => Some(false),
+        };
+    }
+
+    /// Bounding area of the drawn blocks in the node
+    fn area(&self, areas: &[(BlockType, Rect)]) -> Rect {
+        return areas
+            .iter()
+            .filter(|(b, _)| self.contains(b))
+            .map(|(_, a)| *a)
+            .reduce(|a, b| a.union(b))
+            .unwrap_or_default();
+    }
+
+    /// Sets the size of the nearest node holding the block that is split in the
+    /// direction so that the node ends at `edge`, with `areas` the drawn block areas.
+    /// Some(false) while no node on the path could be resized yet.
+    fn resize_to(
+        &mut self,
+        block_type: &BlockType,
+        direction: Direction,
+        edge: u16,
+        areas: &[(BlockType, Rect)],
+    ) -> Option<bool> {
+        let own_area = self.area(areas);
+        let (children, split_direction) = match self {
+            LayoutNode::Block(b, _) => return (b == block_type).then_some(false),
+            LayoutNode::Row(_, children) => (children, Direction::Horizontal),
+            LayoutNode::Col(_, children) => (children, Direction::Vertical),
+        };
+        let i = children.iter().position(|c| c.contains(block_type))?;
+        if children[i].resize_to(block_type, direction, edge, areas)? {
+            return Some(true);
+        }
+        if split_direction != direction {
+            return Some(false);
+        }
+        // Start and length along the direction
+        let span = |a: Rect| match direction {
+            Direction::Horizontal => (a.x, a.width),
+            Direction::Vertical => (a.y, a.height),
+        };
+        let lengths: Vec<u16> = children.iter().map(|c| span(c.area(areas)).1).collect();
+        let new_len = edge.saturating_sub(span(children[i].area(areas)).0).max(1);
+        let new_size = match children[i].size() {
+            Size::Length(_) => Size::Length(new_len),
+            Size::Min(_) => Size::Min(new_len),
+            Size::Percent(_) => {
+                let percent = new_len as u32 * 100 / span(own_area).1.max(1) as u32;
+                Size::Percent((percent as u16).clamp(5, 95))
+            }
+            Size::Fill(_) => {
+                // Fill weights become lengths, the other fills share the space left
+                let is_fill = |c: &LayoutNode| matches!(c.size(), Size::Fill(_));
+                let fill_len: u16 = (0..children.len())
+                    .filter(|j| is_fill(&children[*j]))
+                    .map(|j| lengths[j])
+                    .sum();
+                let others_len = fill_len - lengths[i];
+                if others_len == 0 {
+                    return Some(false);
+                }
+                let new_len = new_len.min(fill_len.saturating_sub(1)).max(1);
+                let mut left = fill_len - new_len;
+                let mut others_left = others_len;
+                for (j, c) in children.iter_mut().enumerate() {
+                    if j != i && is_fill(c) {
+                        // Rounded so that the shares add up to the space left
+                        let share = (lengths[j] as u32 * left as u32 / others_left as u32) as u16;
+                        others_left -= lengths[j];
+                        left -= share;
+                        c.set_size(Size::Fill(share.max(1)));
+                    }
+                }
+                Size::Fill(new_len)
+            }
+            Size::Auto => return Some(false),
+        };
+        children[i].set_size(new_size);
+        return Some(true);
+    }
+
+    fn size(&self) -> Size {
+        return match self {
+            LayoutNode::Block(_, size) | LayoutNode::Row(size, _) | LayoutNode::Col(size, _) => {
+                *size
+            }
+        };
+    }
+
+    /// Area of every block in the tree, `auto` gives the content size of a block
+    pub fn areas(&self, area: Rect, auto: &impl Fn(&BlockType) -> u16) -> Vec<(BlockType, Rect)> {
+        let (children, direction) = match self {
+            LayoutNode::Block(block_type, _) => return vec![(block_type.clone(), area)],
+            LayoutNode::Row(_, children) => (children, Direction::Horizontal),
+            LayoutNode::Col(_, children) => (children, Direction::Vertical),
+        };
+        let constraints: Vec<Constraint> = children
+            .iter()
+            .map(|c| match c {
+                LayoutNode::Block(block_type, size) => size.constraint(auto(block_type)),
+                _ => c.size().constraint(0),
+            })
+            .collect();
+        let child_areas = Layout::default()
+            .direction(direction)
+            .constraints(constraints)
+            .split(area);
+        return children
+            .iter()
+            .zip(child_areas.iter())
+            .flat_map(|(c, a)| c.areas(*a, auto))
+            .collect();
+    }
+}
+
 pub struct BlockPos {
     pub col: usize,
     pub row: usize,
@@ -49,6 +405,14 @@ impl BlockPos {
     }
 }
 
+/// Focused block of a layout, to restore it later
+#[derive(Clone, Copy, PartialEq)]
+pub struct LayoutFocus {
+    col: usize,
+    row: usize,
+    sublayout_active: bool,
+}
+
 pub struct TuiBlock {
     b_type: BlockType,
     state: BlockState,
@@ -85,6 +449,9 @@ pub struct TuiLayout {
     blocks: Vec<Vec<TuiBlock>>,
     active: BlockPos,
     sublayout_active: bool,
+    tree: Option<LayoutNode>,
+    base_spec: String, // Tree from the config before resizing
+    zoomed: bool,
 }
 
 impl TuiLayout {
@@ -95,6 +462,154 @@ impl TuiLayout {
             blocks: Vec::new(),
             active: BlockPos::default(),
             sublayout_active: false,
+            tree: None,
+            base_spec: String::new(),
+            zoomed: false,
+        }
+    }
+
+    /// Focus columns of the tree are the focusable blocks grouped by their left edge,
+    /// top to bottom in each
+    pub fn from_tree(tree: LayoutNode) -> Result<Self, String> {
+        let mut layout = TuiLayout::new();
+        let mut leaves = tree.areas(Rect::new(0, 0, 1000, 1000), &|_| 10);
+        leaves.retain(|(block_type, _)| block_type.is_focusable());
+        if leaves.is_empty() {
+            return Err("no focusable blocks".to_string());
+        }
+        leaves.sort_by_key(|(_, area)| (area.x, area.y));
+        let mut col_x: Option<u16> = None;
+        for (block_type, area) in leaves {
+            if col_x != Some(area.x) {
+                layout.add_col();
+                col_x = Some(area.x);
+            }
+            let col = layout.blocks.len() - 1;
+            if block_type == BlockType::Search {
+                let sub_layout = layout.add_layout(BlockType::Search, col);
+                sub_layout.add_col();
+                sub_layout.add_block(BlockType::SearchUser, 0);
+                sub_layout.add_block(BlockType::SearchRepo, 0);
+                sub_layout.add_block(BlockType::SearchGlobal, 0);
+            } else {
+                layout.add_block(block_type, col);
+            }
+        }
+        layout.tree = Some(tree);
+        return Ok(layout);
+    }
+
+    /// Layout set as `layout=<tree>` in the config, or the default one, with the
+    /// sizes saved in the last session when the config layout has not changed
+    pub fn from_config(config: &Option<crate::config::Config>) -> Result<Self, String> {
+        let spec = config
+            .as_ref()
+            .and_then(|c| c.layout.clone())
+            .unwrap_or(DEFAULT_LAYOUT.to_string());
+        let mut layout = TuiLayout::from_tree(LayoutNode::parse(&spec)?)?;
+        layout.base_spec = spec.clone();
+        let state = crate::config::load_state();
+        let saved = |key: &str| state.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone());
+        if saved("layout_base") == Some(spec) {
+            if let Some(tree) = saved("layout").and_then(|t| LayoutNode::parse(&t).ok()) {
+                layout.tree = Some(tree);
+            }
+        }
+        return Ok(layout);
+    }
+
+    pub fn default_layout() -> Self {
+        let mut layout = TuiLayout::from_tree(
+            LayoutNode::parse(DEFAULT_LAYOUT).expect("Default layout should parse"),
+        )
+        .expect("Default layout should have blocks");
+        layout.base_spec = DEFAULT_LAYOUT.to_string();
+        return layout;
+    }
+
+    /// Areas of the blocks in the tree, or only the focused block when zoomed
+    pub fn areas(&self, area: Rect, auto: &impl Fn(&BlockType) -> u16) -> Vec<(BlockType, Rect)> {
+        if self.zoomed {
+            return vec![(self.focused_block_type(), area)];
+        }
+        return match &self.tree {
+            Some(tree) => tree.areas(area, auto),
+            None => Vec::new(),
+        };
+    }
+
+    /// Focused block of this layout, not of its sublayouts
+    fn focused_block_type(&self) -> BlockType {
+        return self.blocks[self.active.col][self.active.row].block_type();
+    }
+
+    pub fn toggle_zoom(&mut self) -> bool {
+        self.zoomed = !self.zoomed;
+        return self.zoomed;
+    }
+
+    /// Grows or shrinks the focused block horizontally or vertically
+    pub fn resize_focused(&mut self, horizontal: bool, grow: bool) -> bool {
+        let resized = self.resize_block(&self.focused_block_type(), horizontal, grow);
+        if resized {
+            self.save_state();
+        }
+        return resized;
+    }
+
+    pub fn can_resize(&self, block_type: &BlockType, horizontal: bool) -> bool {
+        let mut layout = TuiLayout::new();
+        layout.tree = self.tree.clone();
+        return layout.resize_block(block_type, horizontal, true);
+    }
+
+    pub fn resize_block(&mut self, block_type: &BlockType, horizontal: bool, grow: bool) -> bool {
+        let direction = match horizontal {
+            true => Direction::Horizontal,
+            false => Direction::Vertical,
+        };
+        return match &mut self.tree {
+            Some(tree) => tree.resize(block_type, direction, grow) == Some(true),
+            None => false,
+        };
+    }
+
+    /// Resizes the block so that its right or bottom edge is at `edge`, with
+    /// `areas` the areas the blocks were drawn in
+    pub fn resize_block_to(
+        &mut self,
+        block_type: &BlockType,
+        horizontal: bool,
+        edge: u16,
+        areas: &[(BlockType, Rect)],
+    ) -> bool {
+        if self.zoomed {
+            return false;
+        }
+        let direction = match horizontal {
+            true => Direction::Horizontal,
+            false => Direction::Vertical,
+        };
+        return match &mut self.tree {
+            Some(tree) => tree.resize_to(block_type, direction, edge, areas) == Some(true),
+            None => false,
+        };
+    }
+
+    /// Restores the sizes of the config layout
+    pub fn reset_sizes(&mut self) {
+        if let Ok(tree) = LayoutNode::parse(&self.base_spec) {
+            self.tree = Some(tree);
+            self.save_state();
+        }
+    }
+
+    pub fn save_state(&self) {
+        if let Some(tree) = &self.tree {
+            crate::config::save_state(vec![
+                ("layout_base".to_string(), self.base_spec.clone()),
+                ("layout".to_string(), tree.to_string()),
+            ]);
         }
     }
 
@@ -130,6 +645,21 @@ impl TuiLayout {
             .expect("Failed to create sublayout")
     }
 
+    pub fn focus(&self) -> LayoutFocus {
+        return LayoutFocus {
+            col: self.active.col,
+            row: self.active.row,
+            sublayout_active: self.sublayout_active,
+        };
+    }
+
+    pub fn set_focus(&mut self, focus: LayoutFocus) {
+        if focus.col < self.blocks.len() && focus.row < self.blocks[focus.col].len() {
+            self.active = BlockPos::new(focus.col, focus.row);
+            self.sublayout_active = focus.sublayout_active && self.active_sublayout().is_some();
+        }
+    }
+
     pub fn active_block_pos(&self) -> &BlockPos {
         return &self.active;
     }
@@ -194,6 +724,25 @@ impl TuiLayout {
         self.active.row = self.active.row % self.blocks[self.active.col].len();
     }
 
+    /// Moves focus to the first block of the given type, entering sublayouts if needed
+    pub fn focus_block(&mut self, block_type: BlockType) -> bool {
+        for col in 0..self.blocks.len() {
+            for row in 0..self.blocks[col].len() {
+                let block = &mut self.blocks[col][row];
+                let found_in_sublayout = match &mut block.sublayout {
+                    Some(sl) => sl.focus_block(block_type.clone()),
+                    None => false,
+                };
+                if block.b_type == block_type || found_in_sublayout {
+                    self.active = BlockPos::new(col, row);
+                    self.sublayout_active = found_in_sublayout;
+                    return true;
+                }
+            }
+        }
+        return false;
+    }
+
     pub fn select_layout(&mut self) {
         if self.active_block().sublayout.is_some() {
             self.sublayout_active = true;
@@ -208,3 +757,82 @@ impl TuiLayout {
         return false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect::new(0, 0, 100, 40);
+
+    fn area_of(tree: &LayoutNode, block_type: BlockType) -> Rect {
+        return tree
+            .areas(AREA, &|_| 3)
+            .into_iter()
+            .find(|(b, _)| *b == block_type)
+            .map(|(_, a)| a)
+            .unwrap();
+    }
+
+    /// Drags the edge of the block to each position in turn
+    fn drag(spec: &str, block_type: BlockType, direction: Direction, edges: &[u16]) -> LayoutNode {
+        let mut tree = LayoutNode::parse(spec).unwrap();
+        for edge in edges {
+            let areas = tree.areas(AREA, &|_| 3);
+            assert_eq!(
+                tree.resize_to(&block_type, direction, *edge, &areas),
+                Some(true)
+            );
+        }
+        return tree;
+    }
+
+    #[test]
+    fn fill_edge_follows_pointer() {
+        let spec = "row(repos, col[2fr](commits, info))";
+        let tree = drag(spec, BlockType::Repos, Direction::Horizontal, &[30]);
+        assert_eq!(area_of(&tree, BlockType::Repos).right(), 30);
+        assert_eq!(area_of(&tree, BlockType::Commits).x, 30);
+        let tree = drag(spec, BlockType::Commits, Direction::Vertical, &[15]);
+        assert_eq!(area_of(&tree, BlockType::Commits).bottom(), 15);
+        assert_eq!(area_of(&tree, BlockType::Info).y, 15);
+    }
+
+    #[test]
+    fn length_percent_and_min_edges_follow_pointer() {
+        for spec in [
+            "col(info[10], commits)",
+            "col(info[25%], commits)",
+            "col(info[min10], commits)",
+        ] {
+            let tree = drag(spec, BlockType::Info, Direction::Vertical, &[20]);
+            assert_eq!(area_of(&tree, BlockType::Info).bottom(), 20, "{}", spec);
+        }
+    }
+
+    #[test]
+    fn size_depends_on_pointer_not_on_drag_steps() {
+        let spec = "row(repos, commits[2fr], info)";
+        let fast = drag(spec, BlockType::Repos, Direction::Horizontal, &[50]);
+        let slow = drag(
+            spec,
+            BlockType::Repos,
+            Direction::Horizontal,
+            &[34, 36, 40, 45, 49, 50],
+        );
+        assert_eq!(
+            area_of(&fast, BlockType::Repos),
+            area_of(&slow, BlockType::Repos)
+        );
+        assert_eq!(area_of(&fast, BlockType::Repos).right(), 50);
+    }
+
+    #[test]
+    fn auto_sized_blocks_are_not_resized() {
+        let mut tree = LayoutNode::parse("col(profile[auto], repos)").unwrap();
+        let areas = tree.areas(AREA, &|_| 3);
+        assert_eq!(
+            tree.resize_to(&BlockType::Profile, Direction::Vertical, 10, &areas),
+            Some(false)
+        );
+    }
+}
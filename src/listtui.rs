@@ -1,16 +1,17 @@
 use crate::api;
 use crate::command::{Cmd, CmdType};
-use crate::filterlist::FilterList;
-use crate::git::GitUser;
+use crate::filterlist::{list_items, FilterList};
+use crate::git::{GitUser, RepoGroup, RepoSort, Repos};
 use crate::input;
+use crate::keys::{Action, KeyChord, KeyMap, KeyResult};
 use crossterm::event::{Event, KeyCode, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Layout, Margin, Position},
-    style::{Style, Stylize},
+    style::Style,
     text::{Line, Span, Text},
     widgets::{
-        Block, List, ListDirection, ListItem, ListState, Padding, Paragraph, Scrollbar,
-        ScrollbarOrientation, ScrollbarState,
+        Block, List, ListDirection, ListState, Padding, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState,
     },
     Frame,
 };
@@ -39,6 +40,10 @@ struct ListSearchTui {
     mode: ListTuiMode,
     input_mode: InputMode,
     cursor: crate::cursor::Cursor,
+    sort: RepoSort,
+    group: RepoGroup,
+    keymap: KeyMap,
+    theme: crate::theme::Theme,
     // Cursor pos
 }
 
@@ -49,6 +54,8 @@ impl ListSearchTui {
         searched_username: String,
         command: crate::command::CmdType,
         list: FilterList,
+        keymap: KeyMap,
+        theme: crate::theme::Theme,
     ) -> Self {
         Self {
             user,
@@ -59,6 +66,10 @@ impl ListSearchTui {
             mode: ListTuiMode::Full,
             input_mode: InputMode::Repo,
             cursor: crate::cursor::Cursor::new(),
+            sort: RepoSort::Updated,
+            group: RepoGroup::None,
+            keymap,
+            theme,
         }
     }
 
@@ -118,17 +129,32 @@ impl ListSearchTui {
     fn update_selected_user(&mut self, new_gituser: Option<GitUser>) {
         match new_gituser {
             Some(gu) => {
-                let all_repos: Vec<String> = gu.repos.keys().cloned().collect();
-                self.list.set_list(all_repos);
                 self.git_user = Some(gu);
             }
             _ => {
-                let all_repos: Vec<String> = self.user.git.repos.keys().cloned().collect();
                 self.searched_username.clear();
-                self.list.set_list(all_repos);
                 self.git_user = None;
             }
         }
+        self.reset_list();
+    }
+
+    fn shown_repos(&self) -> &Repos {
+        return match &self.git_user {
+            Some(gu) => &gu.repos,
+            None => &self.user.git.repos,
+        };
+    }
+
+    fn reset_list(&mut self) {
+        let (repos, groups) = self.shown_repos().ordered_names(self.sort, self.group);
+        self.list.set_list(repos);
+        self.list.groups = groups;
+    }
+
+    fn reorder_list(&mut self) {
+        let (repos, groups) = self.shown_repos().ordered_names(self.sort, self.group);
+        self.list.reorder(repos, groups);
     }
 
     async fn fetch_new_gituser(&mut self) {
@@ -187,42 +213,56 @@ impl ListSearchTui {
     }
 
     async fn handle_events(&mut self) -> std::io::Result<(bool, Option<Cmd>)> {
-        match crossterm::event::read()? {
-            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
-                KeyCode::Esc => return Ok((true, None)),
-                KeyCode::Up => match self.input_mode {
-                    InputMode::Repo => self.list.state.next(),
-                    _ => {}
-                },
-                KeyCode::Down => match self.input_mode {
-                    InputMode::Repo => self.list.state.previous(),
-                    _ => {}
-                },
-                KeyCode::Left => match self.input_mode {
-                    InputMode::Repo => {
-                        self.cursor.c_left(self.list.filter.len());
-                    }
-                    InputMode::Username => {
-                        self.cursor.c_left(self.searched_username.len());
-                    }
-                },
-
-                KeyCode::Right => {
-                    self.cursor.c_right();
+        let event = match self.keymap.pending_timeout() {
+            Some(timeout) if !crossterm::event::poll(timeout)? => match self.keymap.expire() {
+                Some(key) => Event::Key(key),
+                None => return Ok((false, None)),
+            },
+            _ => crossterm::event::read()?,
+        };
+        let key = match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            _ => return Ok((false, None)),
+        };
+        match key.code {
+            KeyCode::Left => match self.input_mode {
+                InputMode::Repo => {
+                    self.cursor.c_left(self.list.filter.len());
+                }
+                InputMode::Username => {
+                    self.cursor.c_left(self.searched_username.len());
                 }
-                KeyCode::Enter => match self.input_mode {
+            },
+            KeyCode::Right => {
+                self.cursor.c_right();
+            }
+            KeyCode::Tab => self.change_input_mode().await,
+            KeyCode::Backspace => self.handle_backspace(),
+            KeyCode::Char(c) if KeyChord::from_event(&key).is_text() => self.handle_input(c),
+            _ => match self.keymap.handle(&key) {
+                KeyResult::Action(Action::Back | Action::Quit) => return Ok((true, None)),
+                // The list is drawn from the bottom up
+                KeyResult::Action(Action::MoveUp) => self.list.state.next(),
+                KeyResult::Action(Action::MoveDown) => self.list.state.previous(),
+                KeyResult::Action(Action::MoveTop) => self.list.state.last(),
+                KeyResult::Action(Action::MoveBottom) => self.list.state.first(),
+                KeyResult::Action(Action::Open) => match self.input_mode {
                     InputMode::Username => self.fetch_new_gituser().await,
                     InputMode::Repo => {
                         let cmd = self.get_command();
                         return Ok((true, cmd));
                     }
                 },
-                KeyCode::Tab => self.change_input_mode().await,
-                KeyCode::Backspace => self.handle_backspace(),
-                KeyCode::Char(c) => self.handle_input(c),
+                KeyResult::Action(Action::CycleSort) => {
+                    self.sort = self.sort.next();
+                    self.reorder_list();
+                }
+                KeyResult::Action(Action::CycleGroup) => {
+                    self.group = self.group.next();
+                    self.reorder_list();
+                }
                 _ => {}
             },
-            _ => {}
         }
         Ok((false, None))
     }
@@ -231,26 +271,20 @@ impl ListSearchTui {
         let vertical = Layout::vertical([Min(0), Length(1), Length(1)]);
         let [list_area, matches_area, filter_area] = vertical.areas(frame.area());
 
-        let filtered_list = self.list.get_filtered();
-        let mut list_items: Vec<ListItem> = Vec::new();
-
-        let mut list_iter = filtered_list.iter();
-        while let Some(item) = list_iter.next() {
-            let filter_start = item.find(&self.list.filter).unwrap_or(0);
-            let (beg, rest) = item.split_at(filter_start);
-            let (mid, end) = rest.split_at(self.list.filter.len());
-            let li = ListItem::new(Text::from(Line::from(vec![
-                Span::styled(beg, Style::default()),
-                Span::styled(mid, Style::new().green()),
-                Span::styled(end, Style::default()),
-            ])));
-            list_items.push(li);
-        }
-
+        let theme = self.theme.clone();
+        let filtered_list = self.list.get_matches();
+        let (list_items, selected_row) = list_items(
+            &filtered_list,
+            self.list.get_index(),
+            theme.matched,
+            theme.group_header,
+            true,
+            |name| crate::tui::repo_indicators(self.shown_repos(), name, theme.dim),
+        );
         let list_block = List::new(list_items)
             .block(Block::new().padding(Padding::left(2)))
-            .style(Style::new())
-            .highlight_style(Style::new().italic().blue())
+            .style(theme.text)
+            .highlight_style(theme.selection)
             .highlight_symbol("> ")
             .repeat_highlight_symbol(true)
             .direction(ListDirection::BottomToTop);
@@ -259,18 +293,25 @@ impl ListSearchTui {
         let p_matches = Paragraph::new(Text::from(Line::from(vec![
             Span::styled(
                 format!("  {}/{}", filtered_list.len(), self.list.list.len()),
-                Style::new().light_red(),
+                theme.count,
+            ),
+            Span::styled(
+                format!(
+                    "  sort: {}  group: {}",
+                    self.sort.to_string(),
+                    self.group.to_string()
+                ),
+                theme.dim,
             ),
-            Span::styled("", Style::default().gray().dim()),
         ])));
 
         let p_filter = Paragraph::new(Text::from(Line::from(vec![
-            Span::styled("> ", Style::new().blue()),
+            Span::styled("> ", theme.prompt),
             Span::styled(self.list.filter.clone(), Style::default()),
         ])));
 
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalLeft)
-            .thumb_style(Style::new().gray().dim())
+            .thumb_style(theme.dim)
             .track_symbol(None)
             .begin_symbol(None)
             .end_symbol(None);
@@ -281,11 +322,13 @@ impl ListSearchTui {
         let mut list_scrollbar_state = ScrollbarState::new(filtered_list.len())
             .position(filtered_list.len() - self.list.state.state.selected().unwrap_or(0));
 
+        let mut selected_row = selected_row;
         if filtered_list.len() > 0 && self.list.state.state == ListState::default() {
             self.list.state.state.select(Some(0));
+            selected_row = Some(0);
         }
 
-        frame.render_stateful_widget(&list_block, list_area, &mut self.list.state.state);
+        frame.render_stateful_widget(&list_block, list_area, self.list.view_state(selected_row));
         frame.render_stateful_widget(
             scrollbar,
             list_area.inner(scrollbar_margin),
@@ -302,16 +345,17 @@ impl ListSearchTui {
     }
 
     fn draw_search_user(&mut self, frame: &mut Frame) {
+        let theme = &self.theme;
         let vertical = Layout::vertical([Min(0), Length(1), Length(1)]);
         let [_, info_area, input_area] = vertical.areas(frame.area());
 
         let info_text = Paragraph::new(Text::from(Line::from(vec![Span::styled(
             "Input git username:",
-            Style::new().blue(),
+            theme.prompt,
         )])));
 
         let input_line = Paragraph::new(Text::from(Line::from(vec![
-            Span::styled("=> ", Style::new().blue()),
+            Span::styled("=> ", theme.prompt),
             Span::styled(self.searched_username.clone(), Style::default()),
         ])));
 
@@ -337,17 +381,24 @@ pub async fn run_list_selector(
     username: String,
     filter: String,
     command: crate::command::CmdType,
+    keymap: KeyMap,
+    theme: crate::theme::Theme,
 ) -> Result<(), GiermError> {
     let mut list_tui: ListSearchTui;
     if let Some(git_user) = crate::api::search_gituser(&user, &username).await {
-        let all_repos: Vec<String> = git_user.repos.keys().cloned().collect();
+        let (all_repos, _) = git_user
+            .repos
+            .ordered_names(RepoSort::Updated, RepoGroup::None);
         let fl = FilterList::new(all_repos, filter);
-        list_tui = ListSearchTui::new(user, Some(git_user), username, command, fl);
+        list_tui = ListSearchTui::new(user, Some(git_user), username, command, fl, keymap, theme);
     } else {
-        let all_repos: Vec<String> = user.git.repos.keys().cloned().collect();
+        let (all_repos, _) = user
+            .git
+            .repos
+            .ordered_names(RepoSort::Updated, RepoGroup::None);
         let fl = FilterList::new(all_repos, filter);
         let not_found = !username.is_empty() && username != user.git.username;
-        list_tui = ListSearchTui::new(user, None, "".to_string(), command, fl);
+        list_tui = ListSearchTui::new(user, None, "".to_string(), command, fl, keymap, theme);
         if not_found {
             list_tui.input_mode = InputMode::Username;
         }
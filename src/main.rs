@@ -1,78 +1,30 @@
 use std::fs::File;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
+mod ansi;
 mod api;
 mod args;
 mod autocomplete;
+mod clipboard;
 mod command;
+mod config;
 mod cursor;
 mod filterlist;
+mod fuzzy;
 mod git;
+mod graph;
+mod heatmap;
+mod history;
 mod input;
+mod keys;
+mod langbar;
 mod layout;
 mod listtui;
+mod sync;
+mod theme;
 mod tui;
 
 const ACCESS_TOKEN: &str = "GITHUB_ACCESS_TOKEN";
-const CONFIG_FILE: &str = ".giermconfig";
-const CONFIG_PATHS: [&str; 2] = ["/", "/.config/gierm/"];
-
-#[derive(Debug)]
-struct Config {
-    username: Option<String>,
-    password: Option<String>,
-}
-
-impl Config {
-    fn new(username: Option<String>, password: Option<String>) -> Self {
-        Self { username, password }
-    }
-}
-
-fn find_config_file() -> Option<Config> {
-    let mut file: Option<File> = None;
-    for path in CONFIG_PATHS.iter() {
-        match File::open(format!(
-            "{}{}{}",
-            std::env::var("HOME").unwrap_or("~".to_string()),
-            path,
-            CONFIG_FILE
-        )) {
-            Ok(f) => {
-                file = Some(f);
-                break;
-            }
-            Err(_) => continue,
-        }
-    }
-
-    let reader = match file {
-        Some(file) => BufReader::new(file),
-        None => return None,
-    };
-
-    let mut config: Config = Config::new(None, None);
-
-    for line in reader.lines() {
-        match line {
-            Ok(l) => {
-                if l.clone().to_lowercase().starts_with("username") {
-                    if let Some(eq_pos) = l.find('=') {
-                        let (_, username) = l.split_at(eq_pos + 1);
-                        config.username = Some(username.trim().to_string());
-                    }
-                } else if l.clone().to_lowercase().starts_with("password") {
-                    if let Some(eq_pos) = l.find('=') {
-                        let (_, password) = l.split_at(eq_pos + 1);
-                        config.password = Some(password.trim().to_string());
-                    }
-                }
-            }
-            Err(_) => {}
-        }
-    }
-    return Some(config);
-}
 
 fn save_to_file(file_path: String, data: String) {
     let mut file = File::create(&file_path).expect("File creation failed");
@@ -80,21 +32,20 @@ fn save_to_file(file_path: String, data: String) {
     println!("Saved to {}", file_path);
 }
 
-async fn login_user() -> Option<git::User> {
-    let config = find_config_file();
-
+async fn login_user(config: &Option<config::Config>) -> Option<git::User> {
     let mut username: String = String::default();
     let mut password: String = std::env::var(ACCESS_TOKEN).unwrap_or_default();
 
     if password.is_empty() {
-        if let Some(c) = &config {
+        if let Some(c) = config {
             password = c.password.clone().unwrap_or_default();
         }
     }
     if password.is_empty() {
         println!(
             "Set '{}' as environment variable or as 'password' in {} file.",
-            ACCESS_TOKEN, CONFIG_FILE
+            ACCESS_TOKEN,
+            config::CONFIG_FILE
         );
         println!("The config file should be located in '~/' or '~/.config/gierm/'\n");
         println!("Get a Github personal access token from 'https://github.com/settings/tokens'");
@@ -115,11 +66,7 @@ async fn login_user() -> Option<git::User> {
             }
             username = input;
             save_to_file(
-                format!(
-                    "{}/{}",
-                    std::env::var("HOME").unwrap_or("~".to_string()),
-                    CONFIG_FILE
-                ),
+                format!("{}/{}", config::home_dir(), config::CONFIG_FILE),
                 format!("username={}", username),
             );
         }
@@ -135,7 +82,7 @@ async fn login_user() -> Option<git::User> {
     }
 }
 
-async fn clone(user: git::User, args: &args::CLArgs) {
+async fn clone(user: git::User, args: &args::CLArgs, config: &Option<config::Config>) {
     let username =
         match args.username.is_none() || args.username.as_deref() == Some(&user.git.username) {
             true => "".to_string(),
@@ -146,6 +93,8 @@ async fn clone(user: git::User, args: &args::CLArgs) {
         username,
         args.repo.clone().unwrap_or_default(),
         command::CmdType::CLONE,
+        keys::KeyMap::new(config),
+        theme::Theme::new(config),
     )
     .await;
 }
@@ -166,9 +115,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    let config = config::find_config_file();
+
     print!("Fetching user...");
     std::io::stdout().flush().unwrap();
-    let user = match login_user().await {
+    let user = match login_user(&config).await {
         Some(user) => user,
         None => {
             println!("\x1b[2K\rLogin failed..");
@@ -179,15 +130,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     std::io::stdout().flush().unwrap();
 
     if let Some(cmd) = &args.command {
+        // Sync runs its own git commands in each repo
+        if cmd == "sync" {
+            sync::run_sync(&user, &args, &config).await;
+            return Ok(());
+        }
         match command::command_type(cmd) {
             Some(command::CmdType::CLONE) => {
-                clone(user, &args).await;
+                clone(user, &args, &config).await;
                 return Ok(());
             }
             _ => {}
         }
     }
-    tui::run_tui(user).await;
+    tui::run_tui(user, &config).await;
 
     return Ok(());
 }
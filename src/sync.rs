@@ -0,0 +1,167 @@
+use crate::api;
+use crate::args::CLArgs;
+use crate::command::{git_in, Cmd, CmdType};
+use crate::config::Config;
+use crate::git;
+use std::path::Path;
+
+enum SyncStatus {
+    Cloned,
+    Updated,
+    Archived,
+    Renamed(String),
+    Deleted,
+    NotGitRepo,
+    Failed(String),
+}
+
+impl SyncStatus {
+    fn to_string(&self) -> String {
+        return match self {
+            SyncStatus::Cloned => "cloned".to_string(),
+            SyncStatus::Updated => "updated".to_string(),
+            SyncStatus::Archived => "archived upstream, skipped".to_string(),
+            SyncStatus::Renamed(new_name) => format!("renamed upstream to '{}'", new_name),
+            SyncStatus::Deleted => "deleted upstream".to_string(),
+            SyncStatus::NotGitRepo => "not a git repository, skipped".to_string(),
+            SyncStatus::Failed(e) => format!("failed: {}", e.trim()),
+        };
+    }
+}
+
+fn glob_to_regex(pattern: &str) -> Option<regex::Regex> {
+    let mut re = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    return regex::Regex::new(&re).ok();
+}
+
+fn matches_any(patterns: &Vec<String>, name: &str) -> bool {
+    return patterns
+        .iter()
+        .filter_map(|p| glob_to_regex(p))
+        .any(|re| re.is_match(name));
+}
+
+/// Include globs limit the synced repos when set, exclude globs always win.
+fn is_included(config: &Option<Config>, name: &str) -> bool {
+    if let Some(c) = config {
+        if !c.sync_include.is_empty() && !matches_any(&c.sync_include, name) {
+            return false;
+        }
+        return !matches_any(&c.sync_exclude, name);
+    }
+    return true;
+}
+
+fn clone_repo(repo: &git::Repo, dir: &Path, ssh: bool) -> SyncStatus {
+    let mut cmd = Cmd::new_git_cmd(CmdType::CLONE);
+    cmd.push_arg(git::get_clone_url(&repo.user, &repo.name, ssh));
+    cmd.push_arg(dir.to_string_lossy().to_string());
+    return match cmd.exec(true) {
+        Ok(_) => SyncStatus::Cloned,
+        Err((_, e)) => SyncStatus::Failed(e),
+    };
+}
+
+fn update_repo(dir: &Path) -> SyncStatus {
+    if !dir.join(".git").exists() {
+        return SyncStatus::NotGitRepo;
+    }
+    if let Err((_, e)) = git_in(dir, &["fetch", "--prune"]).exec(true) {
+        return SyncStatus::Failed(e);
+    }
+    return match git_in(dir, &["pull", "--ff-only"]).exec(true) {
+        Ok(_) => SyncStatus::Updated,
+        Err((_, e)) => SyncStatus::Failed(e),
+    };
+}
+
+fn local_repo_dirs(root: &Path) -> Vec<String> {
+    let mut dirs: Vec<String> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(root) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && path.join(".git").exists() {
+                dirs.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+    dirs.sort();
+    return dirs;
+}
+
+pub async fn run_sync(user: &git::User, args: &CLArgs, config: &Option<Config>) {
+    let owner = args
+        .org
+        .clone()
+        .or(args.username.clone())
+        .unwrap_or(user.git.username.clone());
+    let root = crate::config::expand_home(&args.root.clone().unwrap_or(".".to_string()));
+    if let Err(e) = std::fs::create_dir_all(&root) {
+        println!("Failed to create '{}': {}", root.display(), e);
+        return;
+    }
+    let ssh = owner.to_lowercase() == user.git.username.to_lowercase();
+
+    println!("Fetching repos of {}...", owner);
+    let repos = match args.org.is_some() {
+        true => api::fetch_org_repos(user, &owner, git::RepoFilter::All).await,
+        false => api::fetch_repos(user, &owner).await,
+    };
+    let synced: Vec<&git::Repo> = repos
+        .sorted(git::RepoSort::Name, git::RepoGroup::None)
+        .into_iter()
+        .filter(|r| r.user.to_lowercase() == owner.to_lowercase())
+        .filter(|r| is_included(config, &r.name))
+        .collect();
+
+    let mut report: Vec<(String, SyncStatus)> = Vec::new();
+    for repo in synced {
+        let name = &repo.name;
+        let dir = root.join(name);
+        print!("\x1b[2K\r{}...", name);
+        std::io::Write::flush(&mut std::io::stdout()).ok();
+        let status = if repo.archived {
+            SyncStatus::Archived
+        } else if dir.exists() {
+            update_repo(&dir)
+        } else {
+            clone_repo(repo, &dir, ssh)
+        };
+        report.push((name.clone(), status));
+    }
+
+    for dir_name in local_repo_dirs(&root) {
+        if repos.contains_key(&dir_name) || !is_included(config, &dir_name) {
+            continue;
+        }
+        match api::fetch_repo(user, &owner, &dir_name).await {
+            Ok(Some(r)) if r.name != dir_name => {
+                report.push((dir_name, SyncStatus::Renamed(r.name)));
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => report.push((dir_name, SyncStatus::Deleted)),
+            Err(e) => report.push((dir_name, SyncStatus::Failed(e))),
+        }
+    }
+    print!("\x1b[2K\r");
+
+    for (name, status) in report.iter() {
+        println!("{:<40} {}", name, status.to_string());
+    }
+    println!(
+        "Synced {} repos into {}",
+        report
+            .iter()
+            .filter(|(_, s)| matches!(s, SyncStatus::Cloned | SyncStatus::Updated))
+            .count(),
+        root.display()
+    );
+}
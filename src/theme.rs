@@ -0,0 +1,197 @@
+use crate::config::Config;
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub text: Style,
+    pub border: Style,
+    pub border_focused: Style,
+    pub border_input: Style,
+    pub selection: Style,
+    pub matched: Style,
+    pub group_header: Style,
+    pub dim: Style,
+    pub key: Style,
+    pub count: Style,
+    pub prompt: Style,
+    pub diff_add: Style,
+    pub diff_del: Style,
+    pub status: Style,
+    pub heat_low: Style,
+    pub heat_mid: Style,
+    pub heat_high: Style,
+    pub heat_max: Style,
+    pub colors: bool, // False when colours are turned off with NO_COLOR
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            text: Style::new().white(),
+            border: Style::default(),
+            border_focused: Style::new().green(),
+            border_input: Style::new().blue(),
+            selection: Style::new().italic().blue(),
+            matched: Style::new().green(),
+            group_header: Style::new().bold().yellow(),
+            dim: Style::new().dark_gray(),
+            key: Style::new().yellow(),
+            count: Style::new().light_red(),
+            prompt: Style::new().blue(),
+            diff_add: Style::new().green(),
+            diff_del: Style::new().red(),
+            status: Style::default(),
+            heat_low: Style::new().fg(Color::Rgb(14, 68, 41)),
+            heat_mid: Style::new().fg(Color::Rgb(0, 109, 50)),
+            heat_high: Style::new().fg(Color::Rgb(38, 166, 65)),
+            heat_max: Style::new().fg(Color::Rgb(57, 211, 83)),
+            colors: true,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            text: Style::new().black(),
+            border: Style::new().gray(),
+            border_focused: Style::new().green(),
+            border_input: Style::new().blue(),
+            selection: Style::new().italic().blue(),
+            matched: Style::new().magenta(),
+            group_header: Style::new().bold().red(),
+            dim: Style::new().gray(),
+            key: Style::new().blue(),
+            count: Style::new().red(),
+            prompt: Style::new().blue(),
+            diff_add: Style::new().green(),
+            diff_del: Style::new().red(),
+            status: Style::new().black(),
+            heat_low: Style::new().fg(Color::Rgb(155, 233, 168)),
+            heat_mid: Style::new().fg(Color::Rgb(64, 196, 99)),
+            heat_high: Style::new().fg(Color::Rgb(48, 161, 78)),
+            heat_max: Style::new().fg(Color::Rgb(33, 110, 57)),
+            colors: true,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            text: Style::new().white(),
+            border: Style::new().white(),
+            border_focused: Style::new().bold().light_yellow(),
+            border_input: Style::new().bold().light_cyan(),
+            selection: Style::new().bold().black().on_light_yellow(),
+            matched: Style::new().bold().underlined().light_cyan(),
+            group_header: Style::new().bold().light_magenta(),
+            dim: Style::new().gray(),
+            key: Style::new().bold().light_yellow(),
+            count: Style::new().bold().light_red(),
+            prompt: Style::new().bold().light_cyan(),
+            diff_add: Style::new().bold().light_green(),
+            diff_del: Style::new().bold().light_red(),
+            status: Style::new().bold().white(),
+            heat_low: Style::new().green(),
+            heat_mid: Style::new().light_green(),
+            heat_high: Style::new().bold().light_green(),
+            heat_max: Style::new().bold().white(),
+            colors: true,
+        }
+    }
+
+    fn preset(name: &str) -> Option<Self> {
+        return match name.to_lowercase().as_str() {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" | "high_contrast" => Some(Theme::high_contrast()),
+            _ => None,
+        };
+    }
+
+    /// The preset set as `theme=<name>` with the `theme.<style>=<colour> <modifiers>`
+    /// overrides of the config, without colours when `NO_COLOR` is set
+    pub fn new(config: &Option<Config>) -> Self {
+        let mut theme = Theme::dark();
+        if let Some(c) = config {
+            if let Some(preset) = c.theme.as_ref().and_then(|t| Theme::preset(t)) {
+                theme = preset;
+            }
+            for (name, value) in c.theme_styles.iter() {
+                if let (Some(style), Some(s)) = (theme.style_mut(name), parse_style(value)) {
+                    *style = s;
+                }
+            }
+        }
+        if std::env::var("NO_COLOR").is_ok_and(|v| !v.is_empty()) {
+            theme = theme.without_colors();
+        }
+        return theme;
+    }
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        return match name {
+            "text" => Some(&mut self.text),
+            "border" => Some(&mut self.border),
+            "border_focused" => Some(&mut self.border_focused),
+            "border_input" => Some(&mut self.border_input),
+            "selection" => Some(&mut self.selection),
+            "matched" => Some(&mut self.matched),
+            "group_header" => Some(&mut self.group_header),
+            "dim" => Some(&mut self.dim),
+            "key" => Some(&mut self.key),
+            "count" => Some(&mut self.count),
+            "prompt" => Some(&mut self.prompt),
+            "diff_add" => Some(&mut self.diff_add),
+            "diff_del" => Some(&mut self.diff_del),
+            "status" => Some(&mut self.status),
+            "heat_low" => Some(&mut self.heat_low),
+            "heat_mid" => Some(&mut self.heat_mid),
+            "heat_high" => Some(&mut self.heat_high),
+            "heat_max" => Some(&mut self.heat_max),
+            _ => None,
+        };
+    }
+
+    /// Keeps only the modifiers, with focus and selection shown by modifiers instead
+    fn without_colors(&self) -> Self {
+        let strip = |style: Style| Style::new().add_modifier(style.add_modifier);
+        Self {
+            text: strip(self.text),
+            border: strip(self.border),
+            border_focused: strip(self.border_focused).bold(),
+            border_input: strip(self.border_input).bold(),
+            selection: strip(self.selection).reversed(),
+            matched: strip(self.matched).underlined(),
+            group_header: strip(self.group_header).bold(),
+            dim: strip(self.dim),
+            key: strip(self.key).bold(),
+            count: strip(self.count),
+            prompt: strip(self.prompt),
+            diff_add: strip(self.diff_add),
+            diff_del: strip(self.diff_del),
+            status: strip(self.status),
+            heat_low: strip(self.heat_low),
+            heat_mid: strip(self.heat_mid),
+            heat_high: strip(self.heat_high),
+            heat_max: strip(self.heat_max).bold(),
+            colors: false,
+        }
+    }
+}
+
+/// Parses a style like `light_blue bold` or `#ff8800 on black italic`
+fn parse_style(value: &str) -> Option<Style> {
+    let mut style = Style::new();
+    let mut words = value.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word.to_lowercase().as_str() {
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style.add_modifier(Modifier::REVERSED),
+            "on" => style.bg(Color::from_str(words.next()?).ok()?),
+            w => style.fg(Color::from_str(w).ok()?),
+        };
+    }
+    return Some(style);
+}
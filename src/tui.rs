@@ -1,45 +1,113 @@
+use crate::keys::{Action, KeyChord, KeyMap, KeyResult};
 use crate::layout::*;
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
+};
 use ratatui::{
-    layout::{Constraint, Layout, Margin},
-    style::{Color, Modifier, Style, Stylize},
+    layout::{Constraint, Flex, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{
-        Block, BorderType, Borders, List, ListDirection, ListItem, ListState, Padding, Paragraph,
-        Scrollbar, ScrollbarOrientation, ScrollbarState,
+        Block, BorderType, Borders, Clear, List, ListDirection, ListItem, ListState, Padding,
+        Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
     },
     Frame,
 };
-use Constraint::{Fill, Length, Min};
+use Constraint::{Length, Min};
 
-pub async fn run_tui(user: crate::git::User) {
-    let mut tui = Tui::new(user, "".to_string(), "".to_string(), "".to_string());
+pub async fn run_tui(user: crate::git::User, config: &Option<crate::config::Config>) {
+    let (layout, status_text) = match TuiLayout::from_config(config) {
+        Ok(layout) => (layout, "".to_string()),
+        Err(e) => (
+            TuiLayout::default_layout(),
+            format!("Invalid layout in config: {}", e),
+        ),
+    };
+    let keymap = KeyMap::new(config);
+    let status_text = match keymap.conflicts.first() {
+        Some(conflict) if status_text.is_empty() => format!("Key conflict in config: {}", conflict),
+        _ => status_text,
+    };
+    let mut tui = Tui::new(
+        user,
+        keymap,
+        crate::theme::Theme::new(config),
+        layout,
+        "".to_string(),
+        "".to_string(),
+        status_text,
+    );
     tui.run().await;
 }
 
-// TODO : better layout?
-fn create_layout(layout: &mut TuiLayout) {
-    layout.add_col();
-    layout.add_block(BlockType::Profile, 0);
-    layout.add_block(BlockType::Repos, 0);
-    let sub_layout = layout.add_layout(BlockType::Search, 0);
-    sub_layout.add_col();
-    sub_layout.add_block(BlockType::SearchUser, 0);
-    sub_layout.add_block(BlockType::SearchRepo, 0);
-    layout.add_col();
-    layout.add_block(BlockType::Info, 1);
-    layout.add_block(BlockType::Commits, 1);
-    layout.add_block(BlockType::CommitInfo, 1);
+/// Sha of the marked commit if it was marked in the given repo
+fn marked_sha_in<'a>(
+    marked: &'a Option<(String, String, String)>,
+    repo: &crate::git::Repo,
+) -> Option<&'a String> {
+    return match marked {
+        Some((owner, name, sha)) if *owner == repo.user && *name == repo.name => Some(sha),
+        _ => None,
+    };
+}
+
+/// Commits with their lanes in the commit graph and the branches they are the head of
+fn commit_list_lines<'a>(
+    repo: &crate::git::Repo,
+    marked: Option<&String>,
+    theme: &crate::theme::Theme,
+) -> Vec<Line<'a>> {
+    // Lanes of a filtered list would miss the commits between the shown ones
+    let graph = match repo.commit_filter.is_local() {
+        true => vec![String::new(); repo.commits.len()],
+        false => crate::graph::graph_rows(&repo.commits),
+    };
+    let has_checks = repo.checks.values().any(|c| c.is_some());
+    return repo
+        .commits
+        .iter()
+        .zip(graph)
+        .filter(|(c, _)| repo.commit_filter.matches(c))
+        .map(|(c, lanes)| {
+            let mut spans = vec![Span::styled(lanes, theme.dim)];
+            match repo.checks.get(&c.sha).and_then(|c| c.as_ref()) {
+                Some(check) => spans.push(Span::styled(
+                    format!(
+                        "{} ",
+                        crate::git::run_symbol(&check.status, &check.conclusion)
+                    ),
+                    run_style(&check.status, &check.conclusion, theme),
+                )),
+                None if has_checks => spans.push(Span::raw("  ")),
+                None => {}
+            }
+            if marked == Some(&c.sha) {
+                spans.push(Span::styled("[base] ", theme.matched));
+            }
+            let branches = repo.branches_at(&c.sha);
+            if !branches.is_empty() {
+                spans.push(Span::styled(
+                    format!("({}) ", branches.join(", ")),
+                    theme.key,
+                ));
+            }
+            spans.push(Span::raw(c.to_string()));
+            return Line::from(spans);
+        })
+        .collect();
 }
 
-fn commit_info_text(commit: &crate::git::Commit) -> Vec<Line<'_>> {
+fn commit_info_text<'a>(
+    commit: &'a crate::git::Commit,
+    theme: &crate::theme::Theme,
+) -> Vec<Line<'a>> {
     let mut commit_info_lines: Vec<Line<'_>> = vec![];
     commit_info_lines.push(Line::from(vec![Span::styled(
         "commit ".to_string() + &commit.sha.to_string(),
         Style::default(),
     )]));
     commit_info_lines.push(Line::from(vec![Span::styled(
-        "Author: ".to_string() + &commit.committer,
+        "Author: ".to_string() + &commit.author,
         Style::default(),
     )]));
     commit_info_lines.push(Line::from(vec![Span::styled(
@@ -62,16 +130,236 @@ fn commit_info_text(commit: &crate::git::Commit) -> Vec<Line<'_>> {
         let files_len = info.files.len().to_string();
         commit_info_lines.push(Line::from(vec![
             Span::styled(files_len + " files changed, ", Style::default()),
-            Span::styled(additions + " insertions(+), ", Style::default()),
-            Span::styled(deletions + " deletions(-)", Style::default()),
+            Span::styled(additions + " insertions(+)", theme.diff_add),
+            Span::styled(", ", Style::default()),
+            Span::styled(deletions + " deletions(-)", theme.diff_del),
         ]));
+        if !info.checks.is_empty() {
+            commit_info_lines.push(Line::default());
+        }
+        for check in info.checks.iter() {
+            commit_info_lines.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "{} ",
+                        crate::git::run_symbol(&check.status, &check.conclusion)
+                    ),
+                    run_style(&check.status, &check.conclusion, theme),
+                ),
+                Span::raw(format!("{}  ", check.name)),
+                Span::styled(check.url.clone(), theme.dim),
+            ]));
+        }
     }
     return commit_info_lines;
 }
 
+/// Lines of a patch coloured by the kind of change
+fn diff_lines(patch: &String, theme: &crate::theme::Theme) -> Vec<Line<'static>> {
+    // Patches are kept as JSON strings, binary and large files have none
+    let text = match serde_json::from_str::<Option<String>>(patch) {
+        Ok(Some(text)) => text,
+        Ok(None) => return vec![Line::styled("No patch for this file", theme.dim)],
+        Err(_) => patch.clone(),
+    };
+    return text
+        .lines()
+        .map(|l| {
+            let style = match l {
+                _ if l.starts_with("@@") => theme.key,
+                _ if l.starts_with('+') => theme.diff_add,
+                _ if l.starts_with('-') => theme.diff_del,
+                _ => Style::default(),
+            };
+            Line::styled(l.to_string(), style)
+        })
+        .collect();
+}
+
+/// Lines of markdown with the headings, lists and code blocks styled
+fn markdown_lines(text: &String, theme: &crate::theme::Theme) -> Vec<Line<'static>> {
+    let mut in_code = false;
+    let mut lines = Vec::new();
+    for l in text.lines() {
+        let trimmed = l.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(Line::styled(format!("  {}", l), theme.dim));
+            continue;
+        }
+        let l = l.replace("**", "").replace("__", "");
+        lines.push(match trimmed {
+            _ if trimmed.starts_with('#') => Line::styled(
+                trimmed.trim_start_matches('#').trim().to_string(),
+                theme.key,
+            ),
+            _ if trimmed.starts_with("- ") || trimmed.starts_with("* ") => {
+                let indent = l.len() - l.trim_start().len();
+                Line::raw(format!("{}• {}", " ".repeat(indent), &l.trim_start()[2..]))
+            }
+            _ => Line::raw(l),
+        });
+    }
+    return lines;
+}
+
+fn run_style(status: &str, conclusion: &str, theme: &crate::theme::Theme) -> Style {
+    return match (status, conclusion) {
+        ("completed", "success") => theme.diff_add,
+        ("completed", "failure" | "timed_out" | "startup_failure") => theme.diff_del,
+        ("completed", _) => theme.dim,
+        _ => theme.key,
+    };
+}
+
+/// Job and step of each row in the jobs view of the Actions block
+fn job_rows(run: &crate::git::WorkflowRun) -> Vec<(usize, Option<usize>)> {
+    let mut rows = Vec::new();
+    for (i, job) in run.jobs.iter().enumerate() {
+        rows.push((i, None));
+        rows.extend((0..job.steps.len()).map(|s| (i, Some(s))));
+    }
+    return rows;
+}
+
+/// Rows of the Actions block, the runs of the repo or the jobs and steps of the opened run
+fn action_lines(
+    repo: &crate::git::Repo,
+    run_id: Option<u64>,
+    theme: &crate::theme::Theme,
+) -> Vec<Line<'static>> {
+    let symbol = |status: &str, conclusion: &str| {
+        Span::styled(
+            format!("{} ", crate::git::run_symbol(status, conclusion)),
+            run_style(status, conclusion, theme),
+        )
+    };
+    if let Some(run) = run_id.and_then(|id| repo.runs.iter().find(|r| r.id == id)) {
+        return job_rows(run)
+            .into_iter()
+            .map(|(j, s)| {
+                let job = &run.jobs[j];
+                match s.map(|s| &job.steps[s]) {
+                    Some(step) => Line::from(vec![
+                        Span::raw("    "),
+                        symbol(&step.status, &step.conclusion),
+                        Span::raw(format!("{:>2} {} ", step.number, step.name)),
+                        Span::styled(
+                            crate::git::duration_string(step.started_at, step.completed_at),
+                            theme.dim,
+                        ),
+                    ]),
+                    None => Line::from(vec![
+                        symbol(&job.status, &job.conclusion),
+                        Span::raw(format!("{} ", job.name)),
+                        Span::styled(
+                            crate::git::duration_string(job.started_at, job.completed_at),
+                            theme.dim,
+                        ),
+                    ]),
+                }
+            })
+            .collect();
+    }
+    return repo
+        .runs
+        .iter()
+        .map(|run| {
+            Line::from(vec![
+                symbol(&run.status, &run.conclusion),
+                Span::styled(format!("#{:<5}", run.number), theme.count),
+                Span::raw(format!("{} ", run.name)),
+                Span::styled(format!("{} ", run.title), theme.dim),
+                Span::styled(format!("{} ", run.branch), theme.key),
+                Span::styled(format!("{} {}", run.event, run.duration()), theme.dim),
+            ])
+        })
+        .collect();
+}
+
+fn commit_file_items(
+    commit: &crate::git::Commit,
+    theme: &crate::theme::Theme,
+) -> Vec<Line<'static>> {
+    return match &commit.info {
+        Some(info) => info
+            .files
+            .iter()
+            .map(|f| {
+                Line::from(vec![
+                    Span::styled(format!("+{:<4} ", f.additions), theme.diff_add),
+                    Span::styled(format!("-{:<4} ", f.deletions), theme.diff_del),
+                    Span::raw(f.name.clone()),
+                ])
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+}
+
+/// Rows of a drawn list, to map mouse clicks to items
+struct ListHit {
+    block_type: BlockType,
+    area: Rect,
+    offset: usize,
+    rows: Vec<Option<usize>>,
+}
+
+impl ListHit {
+    fn new(block_type: BlockType, area: Rect, offset: usize, rows: Vec<Option<usize>>) -> Self {
+        Self {
+            block_type,
+            area,
+            offset,
+            rows,
+        }
+    }
+
+    fn item_at(&self, row: u16) -> Option<usize> {
+        if row < self.area.y || row >= self.area.bottom() {
+            return None;
+        }
+        return *self.rows.get(self.offset + (row - self.area.y) as usize)?;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
 enum Mode {
     Tui,
     Input,
+    Results,
+    CopyMenu,
+    Palette,
+    Help,
+    RefPicker,
+    Compare,
+    Diff,
+    FileHistory,
+    Blame,
+    Prompt,
+    Log,
+    Notifications,
+    Activity,
+    Release,
+}
+
+/// Input asked for in the prompt popup
+#[derive(Clone, Copy, PartialEq)]
+enum PromptKind {
+    CommitFilter,
+    Dispatch,
+    LogSearch,
+}
+
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(percent_y)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    return area;
 }
 
 #[derive(Debug, Default)]
@@ -110,6 +398,29 @@ impl StateL {
         self.state.select(Some(i));
     }
 
+    pub fn first(&mut self) {
+        if self.items_len > 0 {
+            self.state.select(Some(0));
+        }
+    }
+
+    pub fn last(&mut self) {
+        if self.items_len > 0 {
+            self.state.select(Some(self.items_len - 1));
+        }
+    }
+
+    /// Moves the selection for one of the move actions
+    pub fn move_by(&mut self, action: Action) {
+        match action {
+            Action::MoveUp => self.previous(),
+            Action::MoveDown => self.next(),
+            Action::MoveTop => self.first(),
+            Action::MoveBottom => self.last(),
+            _ => {}
+        }
+    }
+
     pub fn new_size(&mut self, n: usize) {
         self.items_len = n;
         if self.items_len < self.get_selected_index().unwrap_or(0) {
@@ -122,25 +433,88 @@ impl StateL {
     }
 }
 
+pub fn repo_indicators(repos: &crate::git::Repos, name: &str, style: Style) -> Vec<Span<'static>> {
+    return match repos.get(&name.to_string()) {
+        Some(repo) => repo
+            .indicators()
+            .iter()
+            .map(|i| Span::styled(format!(" {}", i), style))
+            .collect(),
+        None => Vec::new(),
+    };
+}
+
 struct SearchedUser {
     pub user: crate::git::GitUser,
     repo_list: crate::filterlist::FilterList,
     commit_list: StateL,
+    repo_filter: crate::git::RepoFilter,
 }
 
 impl SearchedUser {
-    pub fn new(user: crate::git::GitUser, filter: String) -> Self {
-        let mut repos: Vec<String> = user.repos.keys().cloned().collect();
-        repos.sort_by_key(|x| {
-            user.repos
-                .get(x)
-                .map(|repo| std::cmp::Reverse(repo.updated_at))
-        });
+    pub fn new(
+        user: crate::git::GitUser,
+        filter: String,
+        sort: crate::git::RepoSort,
+        group: crate::git::RepoGroup,
+    ) -> Self {
+        let (repos, groups) = user.repos.ordered_names(sort, group);
+        let mut repo_list = crate::filterlist::FilterList::new(repos, filter);
+        repo_list.groups = groups;
         Self {
             user,
-            repo_list: crate::filterlist::FilterList::new(repos, filter),
+            repo_list,
             commit_list: StateL::new(0),
+            repo_filter: crate::git::RepoFilter::All,
+        }
+    }
+
+    async fn cycle_repo_filter(
+        &mut self,
+        user: &crate::git::User,
+        sort: crate::git::RepoSort,
+        group: crate::git::RepoGroup,
+    ) -> Option<usize> {
+        if !self.user.is_org {
+            return None;
         }
+        self.repo_filter = self.repo_filter.next();
+        return Some(self.refresh_repos(user, sort, group).await);
+    }
+
+    async fn refresh_repos(
+        &mut self,
+        user: &crate::git::User,
+        sort: crate::git::RepoSort,
+        group: crate::git::RepoGroup,
+    ) -> usize {
+        self.user.repos = match self.user.is_org {
+            true => crate::api::fetch_org_repos(user, &self.user.username, self.repo_filter).await,
+            false => crate::api::fetch_repos(user, &self.user.username).await,
+        };
+        let (repos, groups) = self.user.repos.ordered_names(sort, group);
+        self.repo_list.reorder(repos, groups);
+        self.commit_list = StateL::new(0);
+        return self.user.repos.len();
+    }
+
+    fn select_repo(&mut self, repo_name: &String) -> bool {
+        self.repo_list.set_filter("".to_string());
+        let index = self
+            .repo_list
+            .list
+            .iter()
+            .position(|r| r.to_lowercase() == repo_name.to_lowercase());
+        self.repo_list.state.new_size(self.repo_list.list.len());
+        self.repo_list.state.state.select(index);
+        self.commit_list = StateL::new(0);
+        return index.is_some();
+    }
+
+    fn select_commit(&mut self, sha: &String) -> bool {
+        let index = self.selected_repo().and_then(|r| r.commit_row(sha));
+        self.commit_list.state.select(index);
+        return index.is_some();
     }
 
     fn selected_repo_name(&mut self) -> Option<String> {
@@ -168,8 +542,12 @@ impl SearchedUser {
 
     async fn fetch_selected_commits(&mut self, user: &crate::git::User) -> Option<usize> {
         if let Some(repo) = self.selected_repo_mut() {
-            repo.commits = crate::api::fetch_repo_commits(&user, &repo).await;
-            return Some(repo.commits.len());
+            (repo.commits, repo.languages, repo.branches) = tokio::join!(
+                crate::api::fetch_repo_commits(&user, &repo),
+                crate::api::fetch_repo_languages(&user, &repo),
+                crate::api::fetch_repo_branches(&user, &repo),
+            );
+            return Some(repo.visible_commits().len());
         }
         return None;
     }
@@ -191,21 +569,72 @@ impl SearchedUser {
     }
 
     fn selected_commit(&mut self) -> Option<&crate::git::Commit> {
-        let commit_index = self.commit_list.get_selected_index()?;
+        let row = self.commit_list.get_selected_index()?;
         let repo = self.selected_repo()?;
-        return match commit_index < repo.commits.len() {
-            true => Some(&repo.commits[commit_index]),
-            false => None,
-        };
+        return repo.commits.get(repo.visible_commit_index(row)?);
     }
 
     fn selected_commit_mut(&mut self) -> Option<&mut crate::git::Commit> {
-        let commit_index = self.commit_list.get_selected_index()?;
+        let row = self.commit_list.get_selected_index()?;
         let repo = self.selected_repo_mut()?;
-        return match commit_index < repo.commits.len() {
-            true => repo.commits.get_mut(commit_index),
-            false => None,
-        };
+        let index = repo.visible_commit_index(row)?;
+        return repo.commits.get_mut(index);
+    }
+}
+
+/// What is shown and selected, restored by the navigation history
+#[derive(Clone, PartialEq)]
+struct ViewState {
+    username: Option<String>, // Searched user, None for the own user
+    repo: Option<String>,
+    commit: Option<String>, // Commit sha
+    file: Option<usize>,
+    repo_offset: usize,
+    commit_offset: usize,
+    file_offset: usize,
+    focus: crate::layout::LayoutFocus,
+}
+
+const MAX_CACHED_USERS: usize = 20;
+/// How often the event loop wakes up to check on background commands
+const BACKGROUND_POLL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Selection state of a tab, the active tab keeps its state in `Tui`
+struct Tab {
+    label: String,
+    searched_user: Option<SearchedUser>,
+    show_user_data: bool,
+    repo_list: crate::filterlist::FilterList,
+    commit_list: StateL,
+    file_list: StateL,
+    profile_list: StateL,
+    search_user: String,
+    search_repo: String,
+    focus: crate::layout::LayoutFocus,
+    history: crate::history::History<ViewState>,
+}
+
+impl Tab {
+    fn new(user: &crate::git::User, focus: crate::layout::LayoutFocus) -> Self {
+        let (repos, groups) = user
+            .git
+            .repos
+            .ordered_names(crate::git::RepoSort::Updated, crate::git::RepoGroup::None);
+        let mut repo_list = crate::filterlist::FilterList::new(repos, String::new());
+        repo_list.groups = groups;
+        Self {
+            label: user.git.username.clone(),
+            searched_user: None,
+            show_user_data: true,
+            repo_list,
+            commit_list: StateL::new(0),
+            file_list: StateL::new(0),
+            profile_list: StateL::new(user.git.orgs.len()),
+            search_user: String::new(),
+            search_repo: String::new(),
+            focus,
+            history: crate::history::History::new(),
+        }
     }
 }
 
@@ -213,64 +642,187 @@ struct Tui {
     mode: Mode,
     user: crate::git::User,
     layout: TuiLayout,
-    repo_list_state: StateL,
-    repo_list: Vec<String>,
+    repo_list: crate::filterlist::FilterList,
     commit_list: StateL,
     search_user: String,
     search_repo: String,
     status_text: String,
     searched_user: Option<SearchedUser>,
     show_user_data: bool,
+    profile_list: StateL,
+    search_global: String,
+    search_kind: crate::git::SearchKind,
+    search_results: Vec<crate::git::SearchResult>,
+    search_results_list: StateL,
+    repo_sort: crate::git::RepoSort,
+    repo_group: crate::git::RepoGroup,
+    file_list: StateL,
+    copy_options: Vec<(String, String)>,
+    copy_list: StateL,
+    keymap: KeyMap,
+    theme: crate::theme::Theme,
+    palette: crate::filterlist::FilterList,
+    palette_actions: Vec<Action>,
+    block_areas: Vec<(BlockType, Rect)>,
+    list_hits: Vec<ListHit>,
+    drag: Option<(BlockType, bool)>, // Block being resized and if horizontally
+    tabs: Vec<Tab>,                  // The entry of the active tab is stale
+    active_tab: usize,
+    tab_hits: Vec<(u16, u16)>, // Columns of the tab titles in the tab bar
+    history: crate::history::History<ViewState>,
+    user_cache: Vec<SearchedUser>, // Searched users that are no longer shown
+    marked_commit: Option<(String, String, String)>, // Owner, repo and sha of the commit to compare the selected one with
+    ref_picker: crate::filterlist::FilterList,
+    ref_base: Option<String>, // Base picked in the ref picker, the head is picked next
+    comparison: Option<crate::git::Comparison>,
+    compare_files: StateL,
+    diff: Option<(String, String)>, // File name and patch shown in the diff viewer
+    diff_scroll: usize,
+    diff_return: Mode, // Mode the diff viewer was opened from
+    file_history: Vec<crate::git::Commit>,
+    file_history_path: String,
+    file_history_list: StateL,
+    blame: Option<crate::git::Blame>,
+    blame_list: StateL,
+    prompt: PromptKind,
+    prompt_input: String,
+    prompt_return: Mode, // Mode the prompt was opened from
+    actions_list: StateL,
+    actions_run: Option<u64>, // Run whose jobs are shown in the Actions block
+    log: Option<(String, Vec<String>)>, // Title and lines of the job log
+    log_scroll: usize,
+    log_search: String,
+    log_match: Option<usize>, // Line of the current search match
+    notifications: Vec<crate::git::Notification>, // Grouped by repo
+    notifications_list: StateL,
+    events: Vec<crate::git::Event>,
+    events_list: StateL,
+    events_user: String, // User whose activity feed is fetched
+    releases_list: StateL,
+    release_assets: StateL,
+    release_scroll: usize,
+    download: Option<crate::git::Asset>, // Downloaded outside of the TUI after the next event
+    background: Vec<std::sync::mpsc::Receiver<String>>, // Status of each running command, sent when it finishes
 }
 
 impl Tui {
     pub fn new(
         user: crate::git::User,
+        keymap: KeyMap,
+        theme: crate::theme::Theme,
+        layout: TuiLayout,
         search_user: String,
         search_repo: String,
         status_text: String,
     ) -> Self {
-        let repos_state = StateL::new((&user).git.repos.keys().len());
-        let mut repos: Vec<String> = user.git.repos.keys().cloned().collect();
-        repos.sort_by_key(|x| {
-            user.git
-                .repos
-                .get(x)
-                .map(|repo| std::cmp::Reverse(repo.updated_at))
-        });
-        let mut lo = TuiLayout::new();
-        create_layout(&mut lo);
+        let (repos, groups) = user
+            .git
+            .repos
+            .ordered_names(crate::git::RepoSort::Updated, crate::git::RepoGroup::None);
+        let mut repo_list = crate::filterlist::FilterList::new(repos, search_repo.clone());
+        repo_list.groups = groups;
+        let profile_list = StateL::new(user.git.orgs.len());
+        let tabs = vec![Tab::new(&user, layout.focus())];
         Self {
             mode: Mode::Tui,
             user,
-            layout: lo,
-            repo_list_state: repos_state,
-            repo_list: repos,
+            layout,
+            repo_list,
             commit_list: StateL::new(0),
             search_user,
             search_repo,
             status_text,
             searched_user: None,
             show_user_data: true,
+            profile_list,
+            search_global: String::new(),
+            search_kind: crate::git::SearchKind::Repositories,
+            search_results: Vec::new(),
+            search_results_list: StateL::new(0),
+            repo_sort: crate::git::RepoSort::Updated,
+            repo_group: crate::git::RepoGroup::None,
+            file_list: StateL::new(0),
+            copy_options: Vec::new(),
+            copy_list: StateL::new(0),
+            keymap,
+            theme,
+            palette: crate::filterlist::FilterList::new(Vec::new(), String::new()),
+            palette_actions: Vec::new(),
+            block_areas: Vec::new(),
+            list_hits: Vec::new(),
+            drag: None,
+            tabs,
+            active_tab: 0,
+            tab_hits: Vec::new(),
+            history: crate::history::History::new(),
+            user_cache: Vec::new(),
+            marked_commit: None,
+            ref_picker: crate::filterlist::FilterList::new(Vec::new(), String::new()),
+            ref_base: None,
+            comparison: None,
+            compare_files: StateL::new(0),
+            diff: None,
+            diff_scroll: 0,
+            diff_return: Mode::Tui,
+            file_history: Vec::new(),
+            file_history_path: String::new(),
+            file_history_list: StateL::new(0),
+            blame: None,
+            blame_list: StateL::new(0),
+            prompt: PromptKind::CommitFilter,
+            prompt_input: String::new(),
+            prompt_return: Mode::Tui,
+            actions_list: StateL::new(0),
+            actions_run: None,
+            log: None,
+            log_scroll: 0,
+            log_search: String::new(),
+            log_match: None,
+            notifications: Vec::new(),
+            notifications_list: StateL::new(0),
+            events: Vec::new(),
+            events_list: StateL::new(0),
+            events_user: String::new(),
+            releases_list: StateL::new(0),
+            release_assets: StateL::new(0),
+            release_scroll: 0,
+            download: None,
+            background: Vec::new(),
         }
     }
 
     async fn run(&mut self) {
         let mut terminal = ratatui::init();
+        let _ = crossterm::execute!(std::io::stdout(), event::EnableMouseCapture);
+        self.fetch_notifications().await;
         loop {
+            self.collect_background();
             terminal
                 .draw(|frame| self.draw(frame))
                 .expect("failed to draw frame");
+            if self.fetch_shown_checks().await || self.fetch_focused_block().await {
+                terminal
+                    .draw(|frame| self.draw(frame))
+                    .expect("failed to draw frame");
+            }
             if self.handle_events().await.unwrap_or(true) {
                 break;
             }
+            if let Some(asset) = self.download.take() {
+                let _ = crossterm::execute!(std::io::stdout(), event::DisableMouseCapture);
+                ratatui::restore();
+                self.download_asset(asset).await;
+                terminal = ratatui::init();
+                let _ = crossterm::execute!(std::io::stdout(), event::EnableMouseCapture);
+            }
         }
+        let _ = crossterm::execute!(std::io::stdout(), event::DisableMouseCapture);
         ratatui::restore();
     }
 
-    fn selected_repo_name_user(&self) -> Option<String> {
-        let repo_index = self.repo_list_state.get_selected_index()?;
-        return Some(self.repo_list[repo_index].clone());
+    fn selected_repo_name_user(&mut self) -> Option<String> {
+        let repo_index = self.repo_list.get_index()?;
+        return self.repo_list.get_filtered().get(repo_index).cloned();
     }
 
     fn selected_repo_name_su(&mut self) -> Option<String> {
@@ -278,52 +830,384 @@ impl Tui {
         return su.selected_repo_name();
     }
 
-    fn repo_list_prev(&mut self) {
+    fn repo_list_move(&mut self, action: Action) {
+        self.file_list = StateL::new(0);
+        self.actions_run = None;
+        self.actions_list = StateL::new(0);
+        self.releases_list = StateL::new(0);
         if self.show_su_data() {
             if let Some(su) = &mut self.searched_user {
-                su.repo_list.state.previous();
+                su.repo_list.state.move_by(action);
                 su.commit_list.state = ListState::default();
             }
         } else {
-            self.repo_list_state.previous();
+            self.repo_list.state.move_by(action);
             self.commit_list.state = ListState::default();
         }
     }
 
-    fn repo_list_next(&mut self) {
+    fn commit_list_move(&mut self, action: Action) {
+        self.file_list = StateL::new(0);
         if self.show_su_data() {
             if let Some(su) = &mut self.searched_user {
-                su.repo_list.state.next();
-                su.commit_list.state = ListState::default();
+                su.commit_list.move_by(action);
             }
         } else {
-            self.repo_list_state.next();
-            self.commit_list.state = ListState::default();
+            self.commit_list.move_by(action);
         }
     }
 
-    fn commit_list_prev(&mut self) {
+    fn show_su_data(&self) -> bool {
+        return self.searched_user.is_some() && !self.show_user_data;
+    }
+
+    fn shown_git_user(&self) -> &crate::git::GitUser {
         if self.show_su_data() {
-            if let Some(su) = &mut self.searched_user {
-                su.commit_list.previous();
+            if let Some(su) = &self.searched_user {
+                return &su.user;
             }
-        } else {
-            self.commit_list.previous();
         }
+        return &self.user.git;
     }
 
-    fn commit_list_next(&mut self) {
-        if self.show_su_data() {
-            if let Some(su) = &mut self.searched_user {
-                su.commit_list.next();
+    /// Orgs of a user or members of an org, selectable in the Profile block
+    fn profile_links(&self) -> Vec<String> {
+        let git_user = self.shown_git_user();
+        return match git_user.is_org {
+            true => git_user.members.clone(),
+            false => git_user.orgs.clone(),
+        };
+    }
+
+    fn profile_list_move(&mut self, action: Action) {
+        self.profile_list.items_len = self.profile_links().len();
+        self.profile_list.move_by(action);
+    }
+
+    async fn handle_profile_select(&mut self) {
+        let links = self.profile_links();
+        if let Some(name) = self
+            .profile_list
+            .get_selected_index()
+            .and_then(|i| links.get(i))
+        {
+            self.search_user = name.clone();
+            self.search_repo.clear();
+            self.search().await;
+            self.profile_list = StateL::new(self.profile_links().len());
+        }
+    }
+
+    fn apply_repo_order(&mut self) {
+        self.reorder_repo_lists();
+        self.set_status(format!(
+            "Sorted by {}, grouped by {}",
+            self.repo_sort.to_string(),
+            self.repo_group.to_string()
+        ));
+    }
+
+    fn reorder_repo_lists(&mut self) {
+        let (repos, groups) = self
+            .user
+            .git
+            .repos
+            .ordered_names(self.repo_sort, self.repo_group);
+        self.repo_list.reorder(repos, groups);
+        if let Some(su) = &mut self.searched_user {
+            let (repos, groups) = su.user.repos.ordered_names(self.repo_sort, self.repo_group);
+            su.repo_list.reorder(repos, groups);
+        }
+    }
+
+    /// User and repo shown in the active tab
+    fn tab_label(&mut self) -> String {
+        let username = self.shown_git_user().username.clone();
+        return match self.selected_repo_name() {
+            Some(repo) => format!("{}/{}", username, repo),
+            None => username,
+        };
+    }
+
+    /// Moves the state of the active tab out of `Tui`
+    fn take_tab(&mut self) -> Tab {
+        let fresh = Tab::new(&self.user, self.layout.focus());
+        return Tab {
+            label: self.tab_label(),
+            searched_user: self.searched_user.take(),
+            show_user_data: std::mem::replace(&mut self.show_user_data, fresh.show_user_data),
+            repo_list: std::mem::replace(&mut self.repo_list, fresh.repo_list),
+            commit_list: std::mem::replace(&mut self.commit_list, fresh.commit_list),
+            file_list: std::mem::replace(&mut self.file_list, fresh.file_list),
+            profile_list: std::mem::replace(&mut self.profile_list, fresh.profile_list),
+            search_user: std::mem::take(&mut self.search_user),
+            search_repo: std::mem::take(&mut self.search_repo),
+            focus: self.layout.focus(),
+            history: std::mem::replace(&mut self.history, fresh.history),
+        };
+    }
+
+    fn load_tab(&mut self, tab: Tab) {
+        self.searched_user = tab.searched_user;
+        self.show_user_data = tab.show_user_data;
+        self.repo_list = tab.repo_list;
+        self.commit_list = tab.commit_list;
+        self.file_list = tab.file_list;
+        self.profile_list = tab.profile_list;
+        self.search_user = tab.search_user;
+        self.search_repo = tab.search_repo;
+        self.layout.set_focus(tab.focus);
+        self.history = tab.history;
+        // The order may have changed while the tab was in the background
+        self.reorder_repo_lists();
+    }
+
+    fn switch_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+        let current = self.take_tab();
+        self.tabs[self.active_tab] = current;
+        let next = std::mem::replace(
+            &mut self.tabs[index],
+            Tab::new(&self.user, self.layout.focus()),
+        );
+        self.active_tab = index;
+        self.load_tab(next);
+        self.set_status(format!("Tab {}", index + 1));
+    }
+
+    fn new_tab(&mut self) {
+        self.tabs.push(Tab::new(&self.user, self.layout.focus()));
+        self.switch_tab(self.tabs.len() - 1);
+    }
+
+    fn close_tab(&mut self) {
+        if self.tabs.len() == 1 {
+            self.set_status("Can't close the last tab".to_string());
+            return;
+        }
+        let closed = self.active_tab;
+        let next = match closed + 1 < self.tabs.len() {
+            true => closed + 1,
+            false => closed - 1,
+        };
+        self.switch_tab(next);
+        self.tabs.remove(closed);
+        if self.active_tab > closed {
+            self.active_tab -= 1;
+        }
+        self.set_status(format!("Closed tab {}", closed + 1));
+    }
+
+    fn draw_tab_bar(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let active_label = self.tab_label();
+        let mut spans: Vec<Span> = Vec::new();
+        let mut x = area.x;
+        self.tab_hits.clear();
+        for (i, tab) in self.tabs.iter().enumerate() {
+            let label = match i == self.active_tab {
+                true => &active_label,
+                false => &tab.label,
+            };
+            let title = format!(" {}: {} ", i + 1, label);
+            let width = title.chars().count() as u16;
+            self.tab_hits.push((x, x + width));
+            x += width + 1;
+            spans.push(Span::styled(
+                title,
+                match i == self.active_tab {
+                    true => theme.selection,
+                    false => theme.dim,
+                },
+            ));
+            spans.push(Span::styled("|", theme.border));
+        }
+        spans.pop();
+        frame.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    fn cycle_repo_sort(&mut self) {
+        self.repo_sort = self.repo_sort.next();
+        self.apply_repo_order();
+    }
+
+    fn cycle_repo_group(&mut self) {
+        self.repo_group = self.repo_group.next();
+        self.apply_repo_order();
+    }
+
+    fn repo_targets(&mut self) -> Option<(String, String, Vec<(String, String)>)> {
+        let repo = self.selected_repo()?;
+        let (owner, name) = (repo.user.clone(), repo.name.clone());
+        let targets = vec![
+            ("URL".to_string(), crate::git::get_repo_url(&owner, &name)),
+            (
+                "Clone HTTPS".to_string(),
+                crate::git::get_clone_url(&owner, &name, false),
+            ),
+            (
+                "Clone SSH".to_string(),
+                crate::git::get_clone_url(&owner, &name, true),
+            ),
+            ("Name".to_string(), format!("{}/{}", owner, name)),
+        ];
+        return Some((owner, name, targets));
+    }
+
+    /// Url and other copyable values of the selection in the focused block, url first
+    fn selection_targets(&mut self) -> Vec<(String, String)> {
+        let block_type = self.layout.active_block().block_type();
+        if block_type == BlockType::Profile {
+            let login = self
+                .profile_list
+                .get_selected_index()
+                .and_then(|i| self.profile_links().get(i).cloned())
+                .unwrap_or(self.shown_git_user().username.clone());
+            return vec![
+                ("URL".to_string(), crate::git::get_user_url(&login)),
+                ("Login".to_string(), login),
+            ];
+        }
+        if block_type == BlockType::Releases {
+            return match self.selected_release() {
+                Some(release) => vec![
+                    ("URL".to_string(), release.url.clone()),
+                    ("Tag".to_string(), release.tag.clone()),
+                ],
+                None => Vec::new(),
+            };
+        }
+        if block_type == BlockType::Actions {
+            return match self.selected_run() {
+                Some(run) => vec![
+                    ("URL".to_string(), run.url.clone()),
+                    ("Run ID".to_string(), run.id.to_string()),
+                ],
+                None => Vec::new(),
+            };
+        }
+        let (owner, name, repo_targets) = match self.repo_targets() {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+        let file_index = self.file_list.get_selected_index();
+        let (sha, file, checks) = match self.selected_commit() {
+            Some(c) => (
+                c.sha.clone(),
+                c.info
+                    .as_ref()
+                    .and_then(|info| file_index.and_then(|i| info.files.get(i)))
+                    .map(|f| f.name.clone()),
+                c.info
+                    .as_ref()
+                    .map(|info| {
+                        info.checks
+                            .iter()
+                            .filter(|check| !check.url.is_empty())
+                            .map(|check| (format!("Check {}", check.name), check.url.clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            None => ("".to_string(), None, Vec::new()),
+        };
+        match block_type {
+            BlockType::Repos | BlockType::Info => return repo_targets,
+            BlockType::CommitInfo if file.is_some() => {
+                let path = file.unwrap_or_default();
+                return vec![
+                    (
+                        "URL".to_string(),
+                        crate::git::get_file_url(&owner, &name, &sha, &path),
+                    ),
+                    ("Path".to_string(), path),
+                    ("SHA".to_string(), sha),
+                ];
             }
-        } else {
-            self.commit_list.next();
+            BlockType::Commits | BlockType::CommitInfo if !sha.is_empty() => {
+                let mut targets = vec![
+                    (
+                        "URL".to_string(),
+                        crate::git::get_commit_url(&owner, &name, &sha),
+                    ),
+                    ("SHA".to_string(), sha.clone()),
+                    ("Short SHA".to_string(), sha.chars().take(8).collect()),
+                    ("Name".to_string(), format!("{}/{}", owner, name)),
+                ];
+                targets.extend(checks);
+                return targets;
+            }
+            _ => return Vec::new(),
         }
     }
 
-    fn show_su_data(&self) -> bool {
-        return self.searched_user.is_some() && !self.show_user_data;
+    fn open_selection(&mut self) {
+        match self.selection_targets().first() {
+            Some((_, url)) => match crate::command::open_url(url) {
+                Ok(_) => self.set_status(format!("Opened {}", url)),
+                Err((_, e)) => self.set_status(format!("Failed to open {}: {}", url, e)),
+            },
+            None => self.set_status("Nothing to open".to_string()),
+        }
+    }
+
+    fn open_copy_menu(&mut self) {
+        self.copy_options = self.selection_targets();
+        if self.copy_options.is_empty() {
+            self.set_status("Nothing to copy".to_string());
+            return;
+        }
+        self.copy_list = StateL::new(self.copy_options.len());
+        self.copy_list.next();
+        self.mode = Mode::CopyMenu;
+    }
+
+    fn handle_keys_copy_menu(&mut self, key: KeyEvent) {
+        let action = match self.keymap.handle(&key) {
+            KeyResult::Action(action) => action,
+            _ => return,
+        };
+        match action {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                self.copy_list.move_by(action)
+            }
+            Action::Open | Action::Copy => {
+                if let Some((label, value)) = self
+                    .copy_list
+                    .get_selected_index()
+                    .and_then(|i| self.copy_options.get(i))
+                {
+                    self.status_text = match crate::clipboard::copy(value) {
+                        Ok(_) => format!("Copied {} {}", label, value),
+                        Err(e) => format!("Failed to copy: {}", e),
+                    };
+                }
+                self.mode = Mode::Tui;
+            }
+            Action::Back | Action::Quit => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    async fn cycle_repo_filter(&mut self) {
+        if !self.show_su_data() {
+            return;
+        }
+        if let Some(su) = &mut self.searched_user {
+            if let Some(n) = su
+                .cycle_repo_filter(&self.user, self.repo_sort, self.repo_group)
+                .await
+            {
+                self.status_text = format!(
+                    "Showing {} {} repos of {}",
+                    n,
+                    su.repo_filter.to_string(),
+                    su.user.username
+                );
+            }
+        }
     }
 
     fn set_status(&mut self, status: String) {
@@ -365,12 +1249,9 @@ impl Tui {
                 return su.selected_commit();
             }
         } else {
-            let commit_index = self.commit_list.get_selected_index()?;
+            let row = self.commit_list.get_selected_index()?;
             let repo = self.selected_repo()?;
-            return match commit_index < repo.commits.len() {
-                true => Some(&repo.commits[commit_index]),
-                false => None,
-            };
+            return repo.commits.get(repo.visible_commit_index(row)?);
         }
         return None;
     }
@@ -381,12 +1262,10 @@ impl Tui {
                 return su.selected_commit_mut();
             }
         } else {
-            let commit_index = self.commit_list.get_selected_index()?;
+            let row = self.commit_list.get_selected_index()?;
             let repo = self.selected_repo_mut()?;
-            return match commit_index < repo.commits.len() {
-                true => repo.commits.get_mut(commit_index),
-                false => None,
-            };
+            let index = repo.visible_commit_index(row)?;
+            return repo.commits.get_mut(index);
         }
         return None;
     }
@@ -421,111 +1300,1867 @@ impl Tui {
             }
         } else {
             let mut commits: Vec<crate::git::Commit> = Vec::default();
+            let mut languages: Vec<(String, i64)> = Vec::default();
+            let mut branches: Vec<(String, String)> = Vec::default();
             if let Some(repo_name) = self.selected_repo_name() {
                 let repo = self.user.git.repos.get(&repo_name)?;
-                commits = crate::api::fetch_repo_commits(&self.user, &repo).await;
+                (commits, languages, branches) = tokio::join!(
+                    crate::api::fetch_repo_commits(&self.user, &repo),
+                    crate::api::fetch_repo_languages(&self.user, &repo),
+                    crate::api::fetch_repo_branches(&self.user, &repo),
+                );
             }
             if let Some(repo) = self.selected_repo_mut() {
                 repo.commits = commits;
-                return Some(repo.commits.len());
+                repo.languages = languages;
+                repo.branches = branches;
+                return Some(repo.visible_commits().len());
             }
         }
         return None;
     }
 
+    /// Fetches the runs or releases of the selected repo when their block is
+    /// focused for the first time, true when any were fetched
+    async fn fetch_focused_block(&mut self) -> bool {
+        let block_type = self.layout.active_block().block_type();
+        let (runs_fetched, releases_fetched) = match self.selected_repo() {
+            Some(repo) => (repo.runs_fetched, repo.releases_fetched),
+            None => return false,
+        };
+        match block_type {
+            BlockType::Actions if !runs_fetched => self.fetch_runs().await,
+            BlockType::Releases if !releases_fetched => self.fetch_releases().await,
+            _ => return false,
+        }
+        return true;
+    }
+
+    /// Fetches the check states of the commits shown in the commit list that
+    /// are not cached yet, true when any were fetched
+    async fn fetch_shown_checks(&mut self) -> bool {
+        let (offset, height) = match self
+            .list_hits
+            .iter()
+            .find(|h| h.block_type == BlockType::Commits)
+        {
+            Some(hit) => (hit.offset, hit.area.height as usize),
+            None => return false,
+        };
+        let repo = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => repo,
+            None => return false,
+        };
+        let shas: Vec<String> = (offset..offset + height)
+            .filter_map(|row| repo.visible_commit_index(row))
+            .map(|i| repo.commits[i].sha.clone())
+            .filter(|sha| !repo.checks.contains_key(sha))
+            .collect();
+        if shas.is_empty() {
+            return false;
+        }
+        let states = crate::api::fetch_commit_states(&self.user, repo, &shas).await;
+        if let Some(repo) = self.selected_repo_mut() {
+            // Failed ones are not tried again until the commits are refreshed
+            repo.checks.extend(shas.into_iter().map(|sha| (sha, None)));
+            repo.checks.extend(states);
+        }
+        return true;
+    }
+
+    /// Shows the status of the background commands that finished
+    fn collect_background(&mut self) {
+        let mut finished: Vec<String> = Vec::new();
+        self.background.retain(|rx| match rx.try_recv() {
+            Ok(status) => {
+                finished.push(status);
+                false
+            }
+            Err(std::sync::mpsc::TryRecvError::Empty) => true,
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => false,
+        });
+        for status in finished {
+            self.set_status(status);
+        }
+    }
+
     async fn handle_events(&mut self) -> std::io::Result<bool> {
-        match self.mode {
-            Mode::Tui => match event::read()? {
-                Event::Key(key) if key.kind == KeyEventKind::Press => {
-                    return self.handle_keys_tui(key.code).await;
+        let mut timeout = self.keymap.pending_timeout();
+        if !self.background.is_empty() {
+            timeout = Some(timeout.map_or(BACKGROUND_POLL, |t| t.min(BACKGROUND_POLL)));
+        }
+        let event = match timeout {
+            Some(timeout) if !event::poll(timeout)? => {
+                match self.keymap.pending_timeout() {
+                    Some(left) if left.is_zero() => {}
+                    _ => return Ok(false),
                 }
-                _ => {}
-            },
-            Mode::Input => match event::read()? {
-                Event::Key(key) if key.kind == KeyEventKind::Press => {
-                    self.handle_keys_input(key.code).await;
+                match self.keymap.expire() {
+                    Some(key) => Event::Key(key),
+                    None => return Ok(false),
                 }
-                _ => {}
-            },
+            }
+            _ => event::read()?,
+        };
+        let key = match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => key,
+            Event::Mouse(mouse) => return Ok(self.handle_mouse(mouse).await),
+            _ => return Ok(false),
+        };
+        match self.mode {
+            Mode::Tui => return self.handle_keys_tui(key).await,
+            Mode::Input => self.handle_keys_input(key).await,
+            Mode::Results => self.handle_keys_results(key).await,
+            Mode::CopyMenu => self.handle_keys_copy_menu(key),
+            Mode::Palette => return Ok(self.handle_keys_palette(key).await),
+            Mode::Help => self.mode = Mode::Tui,
+            Mode::RefPicker => self.handle_keys_ref_picker(key).await,
+            Mode::Compare => self.handle_keys_compare(key),
+            Mode::Diff => self.handle_keys_diff(key),
+            Mode::FileHistory | Mode::Blame => self.handle_keys_file_view(key).await,
+            Mode::Prompt => self.handle_keys_prompt(key).await,
+            Mode::Log => self.handle_keys_log(key),
+            Mode::Notifications => self.handle_keys_notifications(key).await,
+            Mode::Activity => self.handle_keys_activity(key).await,
+            Mode::Release => self.handle_keys_release(key),
         }
         Ok(false)
     }
 
-    async fn handle_keys_tui(&mut self, key_code: KeyCode) -> std::io::Result<bool> {
-        match key_code {
-            KeyCode::Char('q') => return Ok(true),
-            KeyCode::Up | KeyCode::Char('k') => match self.layout.active_block().block_type() {
-                BlockType::Repos => self.repo_list_prev(),
-                BlockType::Commits => self.commit_list_prev(),
-                _ => {}
-            },
-            KeyCode::Down | KeyCode::Char('j') => match self.layout.active_block().block_type() {
-                BlockType::Repos => self.repo_list_next(),
-                BlockType::Commits => self.commit_list_next(),
-                _ => {}
-            },
-            KeyCode::Left | KeyCode::Char('h') => {
+    async fn handle_keys_tui(&mut self, key: KeyEvent) -> std::io::Result<bool> {
+        return match self.keymap.handle(&key) {
+            KeyResult::Action(action) => Ok(self.run_action(action).await),
+            _ => Ok(false),
+        };
+    }
+
+    /// Runs the action on the focused block, true when the app should quit
+    async fn run_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::Quit => return true,
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                match self.layout.active_block().block_type() {
+                    BlockType::Profile => self.profile_list_move(action),
+                    BlockType::Repos => self.repo_list_move(action),
+                    BlockType::Commits => self.commit_list_move(action),
+                    BlockType::CommitInfo => self.file_list.move_by(action),
+                    BlockType::Actions => self.actions_list.move_by(action),
+                    BlockType::Releases => self.releases_list.move_by(action),
+                    _ => {}
+                }
+            }
+            Action::FocusPrevBlock => {
                 self.layout.prev_block();
             }
-            KeyCode::Right | KeyCode::Char('l') => {
+            Action::FocusNextBlock => {
                 self.layout.next_block();
             }
-            KeyCode::Enter | KeyCode::Tab => {
+            Action::FocusPrevCol => {
+                self.layout.prev_col();
+            }
+            Action::FocusNextCol => {
+                self.layout.next_col();
+            }
+            Action::Open => {
                 self.set_status("".to_string());
                 self.handle_enter().await;
             }
-            KeyCode::Esc => {
-                self.set_status("".to_string());
-                if !self.layout.unselect_layout() {
-                    if self.layout.active_block_pos().col == 0 {
-                        self.show_user_data = true;
-                    } else {
-                        self.layout.prev_col();
-                    }
+            Action::Refresh => self.refresh().await,
+            Action::OpenBrowser => self.open_selection(),
+            Action::MarkCommit => self.toggle_commit_mark(),
+            Action::Compare => self.start_compare().await,
+            Action::FileHistory => self.open_file_history().await,
+            Action::Blame => self.open_blame().await,
+            Action::FilterCommits => self.open_commit_filter(),
+            Action::RerunWorkflow | Action::CancelWorkflow => {
+                self.run_workflow_action(action).await
+            }
+            Action::DispatchWorkflow => self.open_dispatch(),
+            Action::SearchLog | Action::NextMatch | Action::PrevMatch => self.log_action(action),
+            Action::Notifications => self.mode = Mode::Notifications,
+            Action::MarkRead | Action::MarkDone | Action::Unsubscribe => {}
+            Action::Activity => self.open_activity().await,
+            Action::Copy => self.open_copy_menu(),
+            Action::CycleSort => {
+                if self.layout.active_block().block_type() == BlockType::Repos {
+                    self.cycle_repo_sort();
                 }
             }
-            _ => {}
-        }
-        return Ok(false);
-    }
-
-    async fn handle_keys_input(&mut self, key_code: KeyCode) {
-        match key_code {
-            KeyCode::Backspace => match self.layout.active_block().block_type() {
-                BlockType::SearchUser => {
-                    self.search_user.pop();
-                }
-                BlockType::SearchRepo => {
-                    self.search_repo.pop();
+            Action::CycleGroup => {
+                if self.layout.active_block().block_type() == BlockType::Repos {
+                    self.cycle_repo_group();
                 }
+            }
+            Action::CycleType => match self.layout.active_block().block_type() {
+                BlockType::Repos => self.cycle_repo_filter().await,
+                BlockType::SearchGlobal => self.search_kind = self.search_kind.next(),
                 _ => {}
             },
-            KeyCode::Char(c) => match self.layout.active_block().block_type() {
+            Action::CloneRepo => self.clone_selected_repo(),
+            Action::CheckoutCommit => self.checkout_selected_commit(),
+            Action::Palette => self.open_palette(),
+            Action::HistoryBack => {
+                let current = self.view_state();
+                match self.history.back(current) {
+                    Some(view) => self.restore_view(view),
+                    None => self.set_status("No earlier view".to_string()),
+                }
+            }
+            Action::HistoryForward => {
+                let current = self.view_state();
+                match self.history.forward(current) {
+                    Some(view) => self.restore_view(view),
+                    None => self.set_status("No later view".to_string()),
+                }
+            }
+            Action::NewTab => self.new_tab(),
+            Action::CloseTab => self.close_tab(),
+            Action::NextTab => self.switch_tab((self.active_tab + 1) % self.tabs.len()),
+            Action::PrevTab => {
+                self.switch_tab((self.active_tab + self.tabs.len() - 1) % self.tabs.len())
+            }
+            Action::Zoom => {
+                let zoomed = self.layout.toggle_zoom();
+                self.set_status(if zoomed { "Zoomed in" } else { "Zoomed out" }.to_string());
+            }
+            Action::GrowWidth | Action::ShrinkWidth | Action::GrowHeight | Action::ShrinkHeight => {
+                let horizontal = matches!(action, Action::GrowWidth | Action::ShrinkWidth);
+                let grow = matches!(action, Action::GrowWidth | Action::GrowHeight);
+                if !self.layout.resize_focused(horizontal, grow) {
+                    self.set_status("The focused block can't be resized that way".to_string());
+                }
+            }
+            Action::ResetLayout => {
+                self.layout.reset_sizes();
+                self.set_status("Reset panel sizes".to_string());
+            }
+            Action::Help => self.mode = Mode::Help,
+            Action::Back if self.close_run_jobs() => {}
+            Action::Back => {
+                self.record_view();
+                self.set_status("".to_string());
+                if !self.layout.unselect_layout() {
+                    if self.layout.active_block_pos().col == 0 {
+                        self.show_user_data = true;
+                    } else {
+                        self.layout.prev_col();
+                    }
+                }
+                self.record_view();
+            }
+        }
+        return false;
+    }
+
+    async fn handle_keys_input(&mut self, key: KeyEvent) {
+        if let (true, KeyCode::Char(c)) = (KeyChord::from_event(&key).is_text(), key.code) {
+            match self.layout.active_block().block_type() {
+                BlockType::SearchUser => self.search_user.push(c),
+                BlockType::SearchRepo => self.search_repo.push(c),
+                BlockType::SearchGlobal => self.search_global.push(c),
+                _ => {}
+            }
+            return;
+        }
+        if key.code == KeyCode::Backspace {
+            match self.layout.active_block().block_type() {
                 BlockType::SearchUser => {
-                    self.search_user.push(c);
+                    self.search_user.pop();
                 }
                 BlockType::SearchRepo => {
-                    self.search_repo.push(c);
+                    self.search_repo.pop();
+                }
+                BlockType::SearchGlobal => {
+                    self.search_global.pop();
                 }
                 _ => {}
-            },
-            KeyCode::Left => {
+            }
+            return;
+        }
+        match self.keymap.handle(&key) {
+            KeyResult::Action(Action::FocusPrevBlock) => {
                 self.layout.prev_block();
             }
-            KeyCode::Right => {
+            KeyResult::Action(Action::FocusNextBlock) => {
                 self.layout.next_block();
             }
-            KeyCode::Enter | KeyCode::Tab => {
-                self.handle_enter().await;
+            KeyResult::Action(Action::Open) => self.handle_enter().await,
+            KeyResult::Action(Action::Back | Action::Quit) => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    async fn handle_keys_results(&mut self, key: KeyEvent) {
+        let action = match self.keymap.handle(&key) {
+            KeyResult::Action(action) => action,
+            _ => return,
+        };
+        match action {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                self.search_results_list.move_by(action)
+            }
+            Action::Open => {
+                self.record_view();
+                self.open_search_result().await;
+                self.record_view();
+            }
+            Action::Back | Action::Quit => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    fn block_at(&self, column: u16, row: u16) -> Option<(BlockType, Rect)> {
+        // Later areas are drawn on top, search inputs over the search block
+        return self
+            .block_areas
+            .iter()
+            .rev()
+            .find(|(_, area)| area.contains(ratatui::layout::Position::new(column, row)))
+            .cloned();
+    }
+
+    async fn handle_mouse(&mut self, mouse: MouseEvent) -> bool {
+        let scroll = match mouse.kind {
+            MouseEventKind::ScrollUp => Some(Action::MoveUp),
+            MouseEventKind::ScrollDown => Some(Action::MoveDown),
+            _ => None,
+        };
+        let popup_list = match self.mode {
+            Mode::Tui | Mode::Input | Mode::Prompt => None,
+            Mode::Results => Some(&mut self.search_results_list),
+            Mode::CopyMenu => Some(&mut self.copy_list),
+            Mode::Palette => Some(&mut self.palette.state),
+            Mode::RefPicker => Some(&mut self.ref_picker.state),
+            Mode::Compare => Some(&mut self.compare_files),
+            Mode::FileHistory => Some(&mut self.file_history_list),
+            Mode::Blame => Some(&mut self.blame_list),
+            Mode::Notifications => Some(&mut self.notifications_list),
+            Mode::Activity => Some(&mut self.events_list),
+            Mode::Help => return false,
+            Mode::Diff => {
+                if let Some(action) = scroll {
+                    self.scroll_diff(action);
+                }
+                return false;
+            }
+            Mode::Log => {
+                if let Some(action) = scroll {
+                    self.scroll_log(action);
+                }
+                return false;
+            }
+            Mode::Release => {
+                self.release_scroll = match scroll {
+                    Some(Action::MoveUp) => self.release_scroll.saturating_sub(1),
+                    Some(_) => self.release_scroll + 1,
+                    None => self.release_scroll,
+                };
+                return false;
+            }
+        };
+        if let Some(list) = popup_list {
+            if let Some(action) = scroll {
+                list.move_by(action);
+            }
+            return false;
+        }
+        match mouse.kind {
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((dragged, horizontal)) = self.drag.clone() {
+                    // The border of the block follows the pointer
+                    let edge = match horizontal {
+                        true => mouse.column + 1,
+                        false => mouse.row + 1,
+                    };
+                    self.layout
+                        .resize_block_to(&dragged, horizontal, edge, &self.block_areas);
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => {
+                if self.drag.take().is_some() {
+                    self.layout.save_state();
+                }
             }
-            KeyCode::Esc => {
+            _ => {}
+        }
+        if mouse.kind == MouseEventKind::Down(MouseButton::Left) && mouse.row == 0 {
+            if let Some(i) = self
+                .tab_hits
+                .iter()
+                .position(|(start, end)| (*start..*end).contains(&mouse.column))
+            {
+                self.switch_tab(i);
+            }
+            return false;
+        }
+        let (block_type, area) = match self.block_at(mouse.column, mouse.row) {
+            Some(b) => b,
+            None => return false,
+        };
+        match mouse.kind {
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                self.focus_block(block_type);
+                return self.run_action(scroll.unwrap_or(Action::MoveDown)).await;
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                if mouse.column + 1 == area.right() && self.layout.can_resize(&block_type, true) {
+                    self.drag = Some((block_type, true));
+                    return false;
+                }
+                if mouse.row + 1 == area.bottom() && self.layout.can_resize(&block_type, false) {
+                    self.drag = Some((block_type, false));
+                    return false;
+                }
+                return self.click(block_type, mouse.row).await;
+            }
+            _ => {}
+        }
+        return false;
+    }
+
+    fn focus_block(&mut self, block_type: BlockType) {
+        if self.layout.active_block().block_type() != block_type {
+            self.mode = Mode::Tui;
+            self.layout.unselect_layout();
+            self.layout.focus_block(block_type);
+        }
+    }
+
+    /// Focuses the clicked block and selects the clicked item, opening it when
+    /// it was already selected
+    async fn click(&mut self, block_type: BlockType, row: u16) -> bool {
+        self.focus_block(block_type.clone());
+        if matches!(
+            block_type,
+            BlockType::SearchUser | BlockType::SearchRepo | BlockType::SearchGlobal
+        ) {
+            self.mode = Mode::Input;
+            return false;
+        }
+        let item = self
+            .list_hits
+            .iter()
+            .find(|h| h.block_type == block_type)
+            .and_then(|h| h.item_at(row));
+        let index = match item {
+            Some(i) => i,
+            None => return false,
+        };
+        let selected = match block_type {
+            BlockType::Repos => self.repo_filter_list().state.get_selected_index(),
+            BlockType::Commits => self.commit_state().get_selected_index(),
+            BlockType::CommitInfo => self.file_list.get_selected_index(),
+            BlockType::Actions => self.actions_list.get_selected_index(),
+            BlockType::Releases => self.releases_list.get_selected_index(),
+            _ => return false,
+        };
+        if selected == Some(index) {
+            return self.run_action(Action::Open).await;
+        }
+        self.file_list.state.select(None);
+        match block_type {
+            BlockType::Repos => {
+                self.repo_filter_list().state.state.select(Some(index));
+                self.commit_state().state = ListState::default();
+                self.actions_run = None;
+                self.actions_list = StateL::new(0);
+                self.releases_list = StateL::new(0);
+            }
+            BlockType::Commits => self.commit_state().state.select(Some(index)),
+            BlockType::Actions => self.actions_list.state.select(Some(index)),
+            BlockType::Releases => self.releases_list.state.select(Some(index)),
+            _ => self.file_list.state.select(Some(index)),
+        }
+        return false;
+    }
+
+    fn repo_filter_list(&mut self) -> &mut crate::filterlist::FilterList {
+        if self.show_su_data() {
+            if let Some(su) = &mut self.searched_user {
+                return &mut su.repo_list;
+            }
+        }
+        return &mut self.repo_list;
+    }
+
+    fn commit_state(&mut self) -> &mut StateL {
+        if self.show_su_data() {
+            if let Some(su) = &mut self.searched_user {
+                return &mut su.commit_list;
+            }
+        }
+        return &mut self.commit_list;
+    }
+
+    fn open_palette(&mut self) {
+        let block_type = self.layout.active_block().block_type();
+        self.palette_actions = crate::keys::ACTIONS
+            .iter()
+            .filter(|a| **a != Action::Palette && a.applies_to(&block_type))
+            .copied()
+            .collect();
+        let labels: Vec<String> = self
+            .palette_actions
+            .iter()
+            .map(|a| a.label(&block_type))
+            .collect();
+        self.palette = crate::filterlist::FilterList::new(labels, String::new());
+        self.palette.state.first();
+        self.mode = Mode::Palette;
+    }
+
+    /// Action of the palette entry with the label
+    fn palette_action(&self, label: &str) -> Option<Action> {
+        let index = self.palette.list.iter().position(|l| l == label)?;
+        return self.palette_actions.get(index).copied();
+    }
+
+    async fn handle_keys_palette(&mut self, key: KeyEvent) -> bool {
+        if let (true, KeyCode::Char(c)) = (KeyChord::from_event(&key).is_text(), key.code) {
+            self.palette.filter_append(c);
+            self.palette.state.first();
+            return false;
+        }
+        if key.code == KeyCode::Backspace {
+            self.palette.filter_remove_last();
+            self.palette.state.first();
+            return false;
+        }
+        match self.keymap.handle(&key) {
+            KeyResult::Action(Action::MoveUp) => self.palette.state.previous(),
+            KeyResult::Action(Action::MoveDown) => self.palette.state.next(),
+            KeyResult::Action(Action::Open) => {
                 self.mode = Mode::Tui;
+                let label = self
+                    .palette
+                    .get_index()
+                    .and_then(|i| self.palette.get_filtered().get(i).cloned());
+                if let Some(action) = label.and_then(|l| self.palette_action(&l)) {
+                    return self.run_action(action).await;
+                }
+            }
+            KeyResult::Action(Action::Back | Action::Quit) => self.mode = Mode::Tui,
+            _ => {}
+        }
+        return false;
+    }
+
+    /// Repo of the shown user by name, without the selection borrowing the user
+    fn repo_by_name(&self, name: &String) -> Option<&crate::git::Repo> {
+        return match self.show_su_data() {
+            true => self.searched_user.as_ref()?.user.repos.get(name),
+            false => self.user.git.repos.get(name),
+        };
+    }
+
+    fn toggle_commit_mark(&mut self) {
+        let (owner, name) = match self.selected_repo() {
+            Some(repo) => (repo.user.clone(), repo.name.clone()),
+            None => return self.set_status("No repo selected".to_string()),
+        };
+        let sha = match self.selected_commit() {
+            Some(c) => c.sha.clone(),
+            None => return self.set_status("No commit selected".to_string()),
+        };
+        let mark = (owner, name, sha);
+        if self.marked_commit.as_ref() == Some(&mark) {
+            self.marked_commit = None;
+            return self.set_status("Unmarked commit".to_string());
+        }
+        self.set_status(format!(
+            "Marked {} as compare base, compare with {}",
+            crate::git::short_ref(&mark.2),
+            self.keymap.keys_for(Action::Compare)
+        ));
+        self.marked_commit = Some(mark);
+    }
+
+    /// Sha of the marked commit if it belongs to the selected repo
+    fn marked_sha(&mut self) -> Option<String> {
+        let marked = self.marked_commit.clone();
+        let repo = self.selected_repo()?;
+        return marked_sha_in(&marked, repo).cloned();
+    }
+
+    /// Compares the marked commit with the selected one, or picks the refs to compare
+    async fn start_compare(&mut self) {
+        let selected = self.selected_commit().map(|c| c.sha.clone());
+        if let (Some(base), Some(head)) = (self.marked_sha(), selected) {
+            if base != head {
+                return self.compare(base, head).await;
+            }
+        }
+        self.open_ref_picker().await;
+    }
+
+    async fn open_ref_picker(&mut self) {
+        let repo_name = match self.selected_repo_name() {
+            Some(name) => name,
+            None => return self.set_status("No repo selected".to_string()),
+        };
+        let tags = match self.repo_by_name(&repo_name) {
+            Some(repo) if repo.tags.is_empty() => {
+                Some(crate::api::fetch_repo_tags(&self.user, repo).await)
+            }
+            _ => None,
+        };
+        if let (Some(tags), Some(repo)) = (tags, self.selected_repo_mut()) {
+            repo.tags = tags;
+        }
+        let mut refs: Vec<String> = Vec::new();
+        refs.extend(self.selected_commit().map(|c| c.sha_short()));
+        if let Some(sha) = self.marked_sha() {
+            refs.push(crate::git::short_ref(&sha));
+        }
+        if let Some(repo) = self.repo_by_name(&repo_name) {
+            refs.extend(repo.branches.iter().map(|(name, _)| name.clone()));
+            refs.extend(repo.tags.iter().map(|(name, _)| name.clone()));
+        }
+        refs.dedup();
+        self.ref_picker = crate::filterlist::FilterList::new(refs, String::new());
+        self.ref_picker.state.first();
+        self.ref_base = None;
+        self.mode = Mode::RefPicker;
+    }
+
+    async fn handle_keys_ref_picker(&mut self, key: KeyEvent) {
+        if let (true, KeyCode::Char(c)) = (KeyChord::from_event(&key).is_text(), key.code) {
+            self.ref_picker.filter_append(c);
+            self.ref_picker.state.first();
+            return;
+        }
+        if key.code == KeyCode::Backspace {
+            self.ref_picker.filter_remove_last();
+            self.ref_picker.state.first();
+            return;
+        }
+        match self.keymap.handle(&key) {
+            KeyResult::Action(Action::MoveUp) => self.ref_picker.state.previous(),
+            KeyResult::Action(Action::MoveDown) => self.ref_picker.state.next(),
+            KeyResult::Action(Action::Open) => {
+                let picked = match self
+                    .ref_picker
+                    .get_index()
+                    .and_then(|i| self.ref_picker.get_filtered().get(i).cloned())
+                {
+                    Some(r) => r,
+                    None => return,
+                };
+                match self.ref_base.take() {
+                    Some(base) => {
+                        self.mode = Mode::Tui;
+                        self.compare(base, picked).await;
+                    }
+                    None => {
+                        self.ref_base = Some(picked);
+                        self.ref_picker.set_filter(String::new());
+                        self.ref_picker.state.first();
+                    }
+                }
+            }
+            KeyResult::Action(Action::Back | Action::Quit) => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    async fn compare(&mut self, base: String, head: String) {
+        let comparison = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => crate::api::fetch_comparison(&self.user, repo, &base, &head).await,
+            None => return self.set_status("No repo selected".to_string()),
+        };
+        match comparison {
+            Some(comparison) => {
+                self.compare_files = StateL::new(comparison.info.files.len());
+                self.compare_files.first();
+                self.comparison = Some(comparison);
+                self.mode = Mode::Compare;
+            }
+            None => self.set_status(format!(
+                "Failed to compare {}...{}",
+                crate::git::short_ref(&base),
+                crate::git::short_ref(&head)
+            )),
+        }
+    }
+
+    fn handle_keys_compare(&mut self, key: KeyEvent) {
+        let action = match self.keymap.handle(&key) {
+            KeyResult::Action(action) => action,
+            _ => return,
+        };
+        match action {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                self.compare_files.move_by(action)
+            }
+            Action::Open => {
+                let file = self.compare_files.get_selected_index().and_then(|i| {
+                    let file = self.comparison.as_ref()?.info.files.get(i)?;
+                    Some((file.name.clone(), file.patch_diff.clone()))
+                });
+                if let Some((name, patch)) = file {
+                    self.open_diff(name, patch);
+                }
+            }
+            Action::OpenBrowser => {
+                let repo = match self.selected_repo() {
+                    Some(repo) => (repo.user.clone(), repo.name.clone()),
+                    None => return,
+                };
+                let url = match &self.comparison {
+                    Some(c) => crate::git::get_compare_url(&repo.0, &repo.1, &c.base, &c.head),
+                    None => return,
+                };
+                if let Err((_, e)) = crate::command::open_url(&url) {
+                    self.set_status(format!("Failed to open {}: {}", url, e));
+                }
+            }
+            Action::Back | Action::Quit => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    fn open_diff(&mut self, name: String, patch: String) {
+        self.diff = Some((name, patch));
+        self.diff_scroll = 0;
+        self.diff_return = self.mode;
+        self.mode = Mode::Diff;
+    }
+
+    fn scroll_diff(&mut self, action: Action) {
+        self.diff_scroll = match action {
+            Action::MoveUp => self.diff_scroll.saturating_sub(1),
+            Action::MoveDown => self.diff_scroll + 1,
+            Action::MoveTop => 0,
+            Action::MoveBottom => usize::MAX, // Clamped to the last page when drawn
+            _ => self.diff_scroll,
+        };
+    }
+
+    fn handle_keys_diff(&mut self, key: KeyEvent) {
+        match self.keymap.handle(&key) {
+            KeyResult::Action(Action::Back | Action::Quit) => self.mode = self.diff_return,
+            KeyResult::Action(action) => self.scroll_diff(action),
+            _ => {}
+        }
+    }
+
+    fn open_prompt(&mut self, prompt: PromptKind, input: String) {
+        self.prompt = prompt;
+        self.prompt_input = input;
+        self.prompt_return = self.mode;
+        self.mode = Mode::Prompt;
+    }
+
+    async fn handle_keys_prompt(&mut self, key: KeyEvent) {
+        if let (true, KeyCode::Char(c)) = (KeyChord::from_event(&key).is_text(), key.code) {
+            self.prompt_input.push(c);
+            return;
+        }
+        match key.code {
+            KeyCode::Backspace => {
+                self.prompt_input.pop();
+            }
+            KeyCode::Enter => match self.prompt {
+                PromptKind::CommitFilter => self.apply_commit_filter().await,
+                PromptKind::Dispatch => self.dispatch_workflow().await,
+                PromptKind::LogSearch => {
+                    self.mode = self.prompt_return;
+                    self.log_search = self.prompt_input.clone();
+                    self.log_match = None;
+                    self.next_log_match(true);
+                }
+            },
+            KeyCode::Esc => self.mode = self.prompt_return,
+            _ => {}
+        }
+    }
+
+    fn open_commit_filter(&mut self) {
+        let query = match self.selected_repo() {
+            Some(repo) => repo.commit_filter.query.clone(),
+            None => return self.set_status("No repo selected".to_string()),
+        };
+        self.open_prompt(PromptKind::CommitFilter, query);
+    }
+
+    /// Filters the commits of the selected repo, refetching them when the server side filters change
+    async fn apply_commit_filter(&mut self) {
+        self.mode = Mode::Tui;
+        let filter = match crate::git::CommitFilter::parse(&self.prompt_input) {
+            Ok(filter) => filter,
+            Err(e) => return self.set_status(e),
+        };
+        let refetch = match self.selected_repo_mut() {
+            Some(repo) => {
+                let refetch = repo.commit_filter.params != filter.params;
+                repo.commit_filter = filter;
+                refetch
             }
+            None => return,
+        };
+        if refetch {
+            self.fetch_selected_commits().await;
+        }
+        let (shown, total) = self
+            .selected_repo()
+            .map(|r| (r.visible_commits().len(), r.commits.len()))
+            .unwrap_or_default();
+        *self.commit_state() = StateL::new(shown);
+        self.file_list = StateL::new(0);
+        self.set_status(format!("Showing {} of {} commits", shown, total));
+    }
+
+    /// Sha of the selected commit and path of the selected file in it
+    fn selected_file_path(&mut self) -> Option<(String, String)> {
+        let index = self.file_list.get_selected_index()?;
+        let commit = self.selected_commit()?;
+        let file = commit.info.as_ref()?.files.get(index)?;
+        return Some((commit.sha.clone(), file.name.clone()));
+    }
+
+    async fn open_file_history(&mut self) {
+        let (sha, path) = match self.selected_file_path() {
+            Some(target) => target,
+            None => return self.set_status("No file selected".to_string()),
+        };
+        let history = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => crate::api::fetch_file_history(&self.user, repo, &sha, &path).await,
+            None => return,
+        };
+        if history.is_empty() {
+            return self.set_status(format!("No history found for {}", path));
+        }
+        self.file_history_list = StateL::new(history.len());
+        self.file_history_list.first();
+        self.file_history = history;
+        self.file_history_path = path;
+        self.mode = Mode::FileHistory;
+    }
+
+    async fn open_blame(&mut self) {
+        let (sha, path) = match self.selected_file_path() {
+            Some(target) => target,
+            None => return self.set_status("No file selected".to_string()),
+        };
+        let blame = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => crate::api::fetch_blame(&self.user, repo, &sha, &path).await,
+            None => return,
+        };
+        match blame {
+            Some(blame) => {
+                self.blame_list = StateL::new(blame.lines.len());
+                self.blame_list.first();
+                self.blame = Some(blame);
+                self.mode = Mode::Blame;
+            }
+            None => self.set_status(format!("Failed to blame {}", path)),
+        }
+    }
+
+    async fn handle_keys_file_view(&mut self, key: KeyEvent) {
+        let action = match self.keymap.handle(&key) {
+            KeyResult::Action(action) => action,
+            _ => return,
+        };
+        let list = match self.mode {
+            Mode::Blame => &mut self.blame_list,
+            _ => &mut self.file_history_list,
+        };
+        match action {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                list.move_by(action)
+            }
+            Action::Open => {
+                let index = list.get_selected_index();
+                let sha = match self.mode {
+                    Mode::Blame => index.and_then(|i| {
+                        let blame = self.blame.as_ref()?;
+                        Some(blame.range_at(i)?.sha.clone())
+                    }),
+                    _ => index.and_then(|i| self.file_history.get(i).map(|c| c.sha.clone())),
+                };
+                if let Some(sha) = sha {
+                    self.jump_to_commit(sha).await;
+                }
+            }
+            Action::Back | Action::Quit => self.mode = Mode::Tui,
             _ => {}
         }
     }
 
+    /// Selects the commit in the Commits block and shows its info
+    async fn jump_to_commit(&mut self, sha: String) {
+        self.mode = Mode::Tui;
+        let index = self.selected_repo().and_then(|r| r.commit_row(&sha));
+        let index = match index {
+            Some(i) => i,
+            None => {
+                return self.set_status(format!(
+                    "Commit {} is not in the fetched or filtered history",
+                    crate::git::short_ref(&sha)
+                ))
+            }
+        };
+        self.record_view();
+        self.commit_state().state.select(Some(index));
+        self.file_list = StateL::new(0);
+        self.handle_commit_select().await;
+        self.layout.focus_block(BlockType::CommitInfo);
+        self.record_view();
+    }
+
+    fn selected_release(&mut self) -> Option<&crate::git::Release> {
+        let index = self.releases_list.get_selected_index()?;
+        return self.selected_repo()?.releases.get(index);
+    }
+
+    fn open_release(&mut self) {
+        let assets = match self.selected_release() {
+            Some(release) => release.assets.len(),
+            None => return,
+        };
+        self.release_assets = StateL::new(assets);
+        self.release_assets.first();
+        self.release_scroll = 0;
+        self.mode = Mode::Release;
+    }
+
+    fn handle_keys_release(&mut self, key: KeyEvent) {
+        let action = match self.keymap.handle(&key) {
+            KeyResult::Action(action) => action,
+            _ => return,
+        };
+        match action {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                self.release_assets.move_by(action)
+            }
+            Action::Open => {
+                let index = self.release_assets.get_selected_index();
+                self.download = self
+                    .selected_release()
+                    .and_then(|r| r.assets.get(index?))
+                    .cloned();
+            }
+            Action::OpenBrowser => {
+                let url = match self.selected_release() {
+                    Some(release) => release.url.clone(),
+                    None => return,
+                };
+                if let Err((_, e)) = crate::command::open_url(&url) {
+                    self.set_status(format!("Failed to open {}: {}", url, e));
+                }
+            }
+            Action::Back | Action::Quit => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    /// Asks for the path outside of the TUI and downloads the asset there with a progress bar
+    async fn download_asset(&mut self, asset: crate::git::Asset) {
+        let input = crate::input::ask_path(format!("Save {} to:", asset.name), &String::new());
+        let mut path = match input {
+            Ok((true, path)) if !path.trim().is_empty() => crate::config::expand_home(path.trim()),
+            _ => return self.set_status("Download cancelled".to_string()),
+        };
+        if path.is_dir() {
+            path = path.join(&asset.name);
+        }
+        if path.exists() {
+            let answer = crate::input::ask_input(
+                format!("{} exists, overwrite it? [y/N]", path.display()),
+                &String::new(),
+            );
+            if !matches!(answer, Ok((true, a)) if a.trim().eq_ignore_ascii_case("y")) {
+                return self.set_status("Download cancelled".to_string());
+            }
+        }
+        println!("Downloading {} to {}", asset.name, path.display());
+        let result = crate::api::download_asset(&self.user, &asset, &path, |done| {
+            crate::input::print_progress(done, asset.size)
+        })
+        .await;
+        println!();
+        match result {
+            Ok(checksum) => {
+                let verified = match asset.digest.is_empty() {
+                    true => ", GitHub has no digest to check it against",
+                    false => ", matches GitHub",
+                };
+                println!("sha256 {}{}", checksum, verified);
+                self.set_status(format!(
+                    "Downloaded {} to {}, sha256 {}{}",
+                    asset.name,
+                    path.display(),
+                    checksum,
+                    verified
+                ));
+            }
+            Err(e) => {
+                println!("Failed to download {}: {}", asset.name, e);
+                self.set_status(format!("Failed to download {}: {}", asset.name, e));
+            }
+        }
+        let _ = crate::input::wait_for_key("Press any key to return");
+    }
+
+    /// Run selected in the Actions block, or the run whose jobs are shown
+    fn selected_run(&mut self) -> Option<&crate::git::WorkflowRun> {
+        let run_id = self.actions_run;
+        let index = self.actions_list.get_selected_index();
+        let repo = self.selected_repo()?;
+        return match run_id {
+            Some(id) => repo.runs.iter().find(|r| r.id == id),
+            None => repo.runs.get(index?),
+        };
+    }
+
+    /// Shows the runs again when the jobs of a run are shown in the Actions block
+    fn close_run_jobs(&mut self) -> bool {
+        if self.layout.active_block().block_type() != BlockType::Actions {
+            return false;
+        }
+        let run_id = match self.actions_run.take() {
+            Some(id) => id,
+            None => return false,
+        };
+        let runs = self.selected_repo().map(|r| r.runs.len()).unwrap_or(0);
+        let index = self
+            .selected_repo()
+            .and_then(|r| r.runs.iter().position(|run| run.id == run_id));
+        self.actions_list = StateL::new(runs);
+        self.actions_list.state.select(index);
+        return true;
+    }
+
+    async fn fetch_releases(&mut self) {
+        let releases = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => crate::api::fetch_releases(&self.user, repo).await,
+            None => return,
+        };
+        self.releases_list = StateL::new(releases.len());
+        if let Some(repo) = self.selected_repo_mut() {
+            repo.releases = releases;
+            repo.releases_fetched = true;
+        }
+    }
+
+    async fn fetch_runs(&mut self) {
+        let runs = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => crate::api::fetch_workflow_runs(&self.user, repo).await,
+            None => return,
+        };
+        let runs_len = runs.len();
+        if let Some(repo) = self.selected_repo_mut() {
+            repo.runs = runs;
+            repo.runs_fetched = true;
+        }
+        match self.actions_run {
+            Some(id) => self.fetch_run_jobs(id).await,
+            None => self.actions_list = StateL::new(runs_len),
+        }
+        self.set_status(format!("Fetched {} workflow runs", runs_len));
+    }
+
+    async fn fetch_run_jobs(&mut self, run_id: u64) {
+        let jobs = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => crate::api::fetch_run_jobs(&self.user, repo, run_id).await,
+            None => return,
+        };
+        let rows = match self
+            .selected_repo_mut()
+            .and_then(|r| r.runs.iter_mut().find(|run| run.id == run_id))
+        {
+            Some(run) => {
+                run.jobs = jobs;
+                job_rows(run).len()
+            }
+            None => return,
+        };
+        self.actions_run = Some(run_id);
+        self.actions_list = StateL::new(rows);
+        self.actions_list.first();
+    }
+
+    async fn handle_actions_select(&mut self) {
+        let (run_id, job_rows) = match self.selected_run() {
+            Some(run) => (run.id, job_rows(run)),
+            None => return,
+        };
+        if self.actions_run.is_none() {
+            return self.fetch_run_jobs(run_id).await;
+        }
+        let (job, step) = match self
+            .actions_list
+            .get_selected_index()
+            .and_then(|i| job_rows.get(i))
+        {
+            Some(row) => *row,
+            None => return,
+        };
+        let (job_id, title, step_name) = match self.selected_run().and_then(|r| r.jobs.get(job)) {
+            Some(job) => {
+                let step_name = step.and_then(|s| job.steps.get(s)).map(|s| s.name.clone());
+                let title = match &step_name {
+                    Some(name) => format!("{} › {}", job.name, name),
+                    None => job.name.clone(),
+                };
+                (job.id, title, step_name)
+            }
+            None => return,
+        };
+        let text = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => crate::api::fetch_job_log(&self.user, repo, job_id).await,
+            None => return,
+        };
+        let text = match text {
+            Some(text) => text,
+            None => return self.set_status(format!("No log for {}", title)),
+        };
+        // Each line starts with a timestamp
+        let lines: Vec<String> = text
+            .trim_start_matches('\u{feff}')
+            .lines()
+            .map(|l| match l.split_once(' ') {
+                Some((time, rest)) if crate::git::parse_time(time).is_some() => rest.to_string(),
+                _ => l.to_string(),
+            })
+            .collect();
+        self.log_scroll = step_name
+            .and_then(|name| {
+                lines
+                    .iter()
+                    .position(|l| crate::ansi::strip(l).contains(&name))
+            })
+            .unwrap_or(0);
+        self.log = Some((title, lines));
+        self.log_match = None;
+        self.mode = Mode::Log;
+    }
+
+    /// Reruns or cancels the selected workflow run
+    async fn run_workflow_action(&mut self, action: Action) {
+        let (run_id, number) = match self.selected_run() {
+            Some(run) => (run.id, run.number),
+            None => return self.set_status("No workflow run selected".to_string()),
+        };
+        let result = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) if action == Action::RerunWorkflow => {
+                crate::api::rerun_workflow_run(&self.user, repo, run_id).await
+            }
+            Some(repo) => crate::api::cancel_workflow_run(&self.user, repo, run_id).await,
+            None => return,
+        };
+        let verb = match action {
+            Action::RerunWorkflow => "rerun",
+            _ => "cancel",
+        };
+        match result {
+            Ok(_) => {
+                self.fetch_runs().await;
+                self.set_status(format!("Requested {} of run #{}", verb, number));
+            }
+            Err(e) => self.set_status(format!("Failed to {} run #{}: {}", verb, number, e)),
+        }
+    }
+
+    fn open_dispatch(&mut self) {
+        let branch = match self.selected_repo() {
+            Some(repo) => repo.default_branch.clone(),
+            None => return self.set_status("No repo selected".to_string()),
+        };
+        if self.selected_run().is_none() {
+            return self.set_status("Select a run of the workflow to dispatch".to_string());
+        }
+        self.open_prompt(PromptKind::Dispatch, branch);
+    }
+
+    /// Runs the workflow of the selected run on the ref and inputs in the prompt
+    async fn dispatch_workflow(&mut self) {
+        self.mode = Mode::Tui;
+        let mut words = self.prompt_input.split_whitespace();
+        let git_ref = match words.next() {
+            Some(r) if !r.contains('=') => r.to_string(),
+            _ => return self.set_status("Give the ref to run the workflow on first".to_string()),
+        };
+        let mut inputs: Vec<(String, String)> = Vec::new();
+        for word in words {
+            match word.split_once('=') {
+                Some((key, value)) => inputs.push((key.to_string(), value.to_string())),
+                None => return self.set_status(format!("Input {} is not key=value", word)),
+            }
+        }
+        let (workflow_id, name) = match self.selected_run() {
+            Some(run) => (run.workflow_id, run.name.clone()),
+            None => return,
+        };
+        let result = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => {
+                crate::api::dispatch_workflow(&self.user, repo, workflow_id, &git_ref, &inputs)
+                    .await
+            }
+            None => return,
+        };
+        match result {
+            Ok(_) => self.set_status(format!("Dispatched {} on {}", name, git_ref)),
+            Err(e) => self.set_status(format!("Failed to dispatch {}: {}", name, e)),
+        }
+    }
+
+    fn scroll_log(&mut self, action: Action) {
+        self.log_scroll = match action {
+            Action::MoveUp => self.log_scroll.saturating_sub(1),
+            Action::MoveDown => self.log_scroll + 1,
+            Action::MoveTop => 0,
+            Action::MoveBottom => usize::MAX, // Clamped to the last page when drawn
+            _ => self.log_scroll,
+        };
+    }
+
+    /// Moves to the next or previous line matching the log search, wrapping around
+    fn next_log_match(&mut self, forward: bool) {
+        let lines = match &self.log {
+            Some((_, lines)) => lines,
+            None => return,
+        };
+        if self.log_search.is_empty() || lines.is_empty() {
+            return;
+        }
+        let query = self.log_search.to_lowercase();
+        let len = lines.len();
+        let start = self.log_match.unwrap_or(match forward {
+            true => self.log_scroll.min(len) + len - 1,
+            false => self.log_scroll.min(len),
+        });
+        let found = (1..=len)
+            .map(|step| match forward {
+                true => (start + step) % len,
+                false => (start + len * 2 - step) % len,
+            })
+            .find(|&i| {
+                crate::ansi::strip(&lines[i])
+                    .to_lowercase()
+                    .contains(&query)
+            });
+        match found {
+            Some(i) => {
+                self.log_match = Some(i);
+                self.log_scroll = i.saturating_sub(3);
+            }
+            None => self.set_status(format!("No match for {}", self.log_search)),
+        }
+    }
+
+    fn handle_keys_log(&mut self, key: KeyEvent) {
+        match self.keymap.handle_log(&key) {
+            KeyResult::Action(Action::Back | Action::Quit) => self.mode = Mode::Tui,
+            KeyResult::Action(action) if action.in_log() => self.log_action(action),
+            KeyResult::Action(action) => self.scroll_log(action),
+            _ => {}
+        }
+    }
+
+    /// Searches the job log or moves between its matches, reopening the last log if closed
+    fn log_action(&mut self, action: Action) {
+        if self.log.is_none() {
+            return self.set_status("No job log open".to_string());
+        }
+        self.mode = Mode::Log;
+        match action {
+            Action::SearchLog => self.open_prompt(PromptKind::LogSearch, self.log_search.clone()),
+            Action::NextMatch => self.next_log_match(true),
+            Action::PrevMatch => self.next_log_match(false),
+            _ => {}
+        }
+    }
+
+    /// Clones the selected repo into the working directory
+    fn clone_selected_repo(&mut self) {
+        let (owner, name) = match self.selected_repo() {
+            Some(repo) => (repo.user.clone(), repo.name.clone()),
+            None => return self.set_status("No repo selected".to_string()),
+        };
+        let ssh = owner.to_lowercase() == self.user.git.username.to_lowercase();
+        let mut cmd = crate::command::Cmd::new_git_cmd(crate::command::CmdType::CLONE);
+        cmd.push_arg(crate::git::get_clone_url(&owner, &name, ssh));
+        cmd.push_arg(name.clone());
+        // Fail instead of prompting for credentials or host keys on the terminal of the TUI
+        cmd.set_env("GIT_TERMINAL_PROMPT", "0");
+        if std::env::var_os("GIT_SSH_COMMAND").is_none() && std::env::var_os("GIT_SSH").is_none() {
+            cmd.set_env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        self.set_status(format!("Cloning {}/{}...", owner, name));
+        std::thread::spawn(move || {
+            let _ = tx.send(match cmd.exec(true) {
+                Ok(_) => format!("Cloned {}/{} into ./{}", owner, name, name),
+                Err((_, e)) => format!("Failed to clone {}/{}: {}", owner, name, e.trim()),
+            });
+        });
+        self.background.push(rx);
+    }
+
+    /// Local clone of the repo, either the working directory or a directory named after the repo in it
+    fn local_repo_dir(owner: &String, name: &String) -> Option<std::path::PathBuf> {
+        let cwd = std::env::current_dir().ok()?;
+        let remote = format!("{}/{}", owner, name).to_lowercase();
+        return [cwd.clone(), cwd.join(name)].into_iter().find(|dir| {
+            crate::command::git_in(dir, &["remote", "get-url", "origin"])
+                .exec(true)
+                .map(|url| {
+                    let url = url.trim().to_lowercase();
+                    url.trim_end_matches(".git").ends_with(&remote)
+                })
+                .unwrap_or(false)
+        });
+    }
+
+    /// Checks out the selected commit in the local clone of its repo
+    fn checkout_selected_commit(&mut self) {
+        let (owner, name) = match self.selected_repo() {
+            Some(repo) => (repo.user.clone(), repo.name.clone()),
+            None => return self.set_status("No repo selected".to_string()),
+        };
+        let sha = match self.selected_commit() {
+            Some(commit) => commit.sha.clone(),
+            None => return self.set_status("No commit selected".to_string()),
+        };
+        let dir = match Self::local_repo_dir(&owner, &name) {
+            Some(dir) => dir,
+            None => return self.set_status(format!("No local clone of {}/{} found", owner, name)),
+        };
+        self.set_status(
+            match crate::command::git_in(&dir, &["checkout", &sha]).exec(true) {
+                Ok(_) => format!(
+                    "Checked out {} in {}",
+                    &sha[..7.min(sha.len())],
+                    dir.display()
+                ),
+                Err((_, e)) => format!("Failed to checkout {}: {}", sha, e.trim()),
+            },
+        );
+    }
+
+    async fn refresh(&mut self) {
+        match self.layout.active_block().block_type() {
+            BlockType::Profile => {
+                crate::api::fetch_rate(&mut self.user).await;
+                self.set_status(format!("Rate limit remaining: {}", self.user.rate()));
+            }
+            BlockType::Repos => {
+                self.file_list = StateL::new(0);
+                let repos_len = if self.show_su_data() {
+                    match &mut self.searched_user {
+                        Some(su) => {
+                            su.refresh_repos(&self.user, self.repo_sort, self.repo_group)
+                                .await
+                        }
+                        None => 0,
+                    }
+                } else {
+                    let username = self.user.git.username.clone();
+                    self.user.git.repos = crate::api::fetch_repos(&self.user, &username).await;
+                    let (repos, groups) = self
+                        .user
+                        .git
+                        .repos
+                        .ordered_names(self.repo_sort, self.repo_group);
+                    self.repo_list.reorder(repos, groups);
+                    self.commit_list = StateL::new(0);
+                    self.user.git.repos.len()
+                };
+                self.set_status(format!("Fetched {} repos", repos_len));
+            }
+            BlockType::Info => {
+                let (owner, name) = match self.selected_repo() {
+                    Some(r) => (r.user.clone(), r.name.clone()),
+                    None => return,
+                };
+                match crate::api::fetch_repo(&self.user, &owner, &name).await {
+                    Ok(Some(mut fetched)) => {
+                        fetched.languages =
+                            crate::api::fetch_repo_languages(&self.user, &fetched).await;
+                        if let Some(repo) = self.selected_repo_mut() {
+                            repo.update_info(fetched);
+                        }
+                        self.set_status(format!("Fetched info of {}", name));
+                    }
+                    Ok(None) => self.set_status(format!("{} no longer exists", name)),
+                    Err(e) => self.set_status(format!("Failed to fetch {}: {}", name, e)),
+                }
+            }
+            BlockType::Commits => {
+                self.file_list = StateL::new(0);
+                if let Some(repo) = self.selected_repo_mut() {
+                    repo.checks.clear();
+                }
+                if let Some(commits_len) = self.fetch_selected_commits().await {
+                    match &mut self.searched_user {
+                        Some(su) if !self.show_user_data => {
+                            su.commit_list = StateL::new(commits_len)
+                        }
+                        _ => self.commit_list = StateL::new(commits_len),
+                    }
+                    self.set_status(format!("Fetched {} commits", commits_len));
+                }
+            }
+            BlockType::Actions => self.fetch_runs().await,
+            BlockType::Releases => {
+                self.fetch_releases().await;
+                let len = self.releases_list.items_len;
+                self.set_status(format!("Fetched {} releases", len));
+            }
+            BlockType::CommitInfo => {
+                self.file_list = StateL::new(0);
+                if let Some(commit) = self.selected_commit_mut() {
+                    commit.info = None;
+                }
+                if let Some(true) = self.fetch_commit_info().await {
+                    if let Some(commit) = self.selected_commit() {
+                        self.status_text =
+                            format!("Fetched commit info for {}", commit.sha_short());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    async fn global_search(&mut self) {
+        if self.search_global.trim().is_empty() {
+            self.mode = Mode::Tui;
+            return;
+        }
+        self.search_results =
+            crate::api::search(&self.user, self.search_kind, &self.search_global).await;
+        self.search_results_list = StateL::new(self.search_results.len());
+        self.search_results_list.next();
+        self.set_status(format!(
+            "Found {} {} results",
+            self.search_results.len(),
+            self.search_kind.to_string()
+        ));
+        self.mode = match self.search_results.is_empty() {
+            true => Mode::Tui,
+            false => Mode::Results,
+        };
+    }
+
+    fn replace_searched_user(&mut self, searched_user: Option<SearchedUser>) {
+        if let Some(old) = std::mem::replace(&mut self.searched_user, searched_user) {
+            self.user_cache
+                .retain(|su| su.user.username.to_lowercase() != old.user.username.to_lowercase());
+            self.user_cache.push(old);
+            if self.user_cache.len() > MAX_CACHED_USERS {
+                self.user_cache.remove(0);
+            }
+        }
+    }
+
+    fn view_state(&mut self) -> ViewState {
+        let username = match self.show_su_data() {
+            true => self
+                .searched_user
+                .as_ref()
+                .map(|su| su.user.username.clone()),
+            false => None,
+        };
+        let repo_offset = self.repo_filter_list().view.offset();
+        let commit_offset = self.commit_state().state.offset();
+        return ViewState {
+            username,
+            repo: self.selected_repo_name(),
+            commit: self.selected_commit().map(|c| c.sha.clone()),
+            file: self.file_list.get_selected_index(),
+            repo_offset,
+            commit_offset,
+            file_offset: self.file_list.state.offset(),
+            focus: self.layout.focus(),
+        };
+    }
+
+    fn record_view(&mut self) {
+        let view = self.view_state();
+        self.history.record(view);
+    }
+
+    /// Shows the view from the data already fetched
+    fn restore_view(&mut self, view: ViewState) {
+        match &view.username {
+            None => self.show_user_data = true,
+            Some(name) => {
+                let is_current = self
+                    .searched_user
+                    .as_ref()
+                    .is_some_and(|su| su.user.username == *name);
+                if !is_current {
+                    let cached = self
+                        .user_cache
+                        .iter()
+                        .position(|su| su.user.username == *name);
+                    match cached {
+                        Some(i) => {
+                            let su = self.user_cache.remove(i);
+                            self.replace_searched_user(Some(su));
+                        }
+                        None => {
+                            self.set_status(format!("{} is no longer cached", name));
+                            return;
+                        }
+                    }
+                }
+                self.show_user_data = false;
+                self.search_user = name.clone();
+            }
+        }
+
+        let repo_list = self.repo_filter_list();
+        let mut index = view
+            .repo
+            .as_ref()
+            .and_then(|r| repo_list.get_filtered().iter().position(|n| n == r));
+        if index.is_none() && view.repo.is_some() {
+            repo_list.set_filter("".to_string());
+            index = view
+                .repo
+                .as_ref()
+                .and_then(|r| repo_list.get_filtered().iter().position(|n| n == r));
+        }
+        repo_list.state.state.select(index);
+        *repo_list.view.offset_mut() = view.repo_offset;
+
+        let commits: Vec<String> = self
+            .selected_repo()
+            .map(|r| {
+                r.visible_commits()
+                    .into_iter()
+                    .map(|i| r.commits[i].sha.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let commit_index = view
+            .commit
+            .as_ref()
+            .and_then(|sha| commits.iter().position(|c| c == sha));
+        let commit_state = self.commit_state();
+        *commit_state = StateL::new(commits.len());
+        commit_state.state.select(commit_index);
+        *commit_state.state.offset_mut() = view.commit_offset;
+
+        let files_len = self
+            .selected_commit()
+            .and_then(|c| c.info.as_ref())
+            .map(|i| i.files.len())
+            .unwrap_or(0);
+        self.file_list = StateL::new(files_len);
+        self.file_list
+            .state
+            .select(view.file.filter(|f| *f < files_len));
+        *self.file_list.state.offset_mut() = view.file_offset;
+
+        self.mode = Mode::Tui;
+        self.layout.set_focus(view.focus);
+        self.set_status(format!(
+            "Showing {}",
+            view.username.unwrap_or(self.user.git.username.clone())
+        ));
+    }
+
+    /// Opens the owner of the selected result and its repo and commit when it has one
+    async fn open_search_result(&mut self) {
+        let (kind, owner, repo, sha) = match self
+            .search_results_list
+            .get_selected_index()
+            .and_then(|i| self.search_results.get(i))
+        {
+            Some(r) => (r.kind, r.owner.clone(), r.repo.clone(), r.sha.clone()),
+            None => return,
+        };
+        self.mode = Mode::Tui;
+        match kind {
+            crate::git::SearchKind::Users => {
+                self.open_user(&owner).await;
+            }
+            _ => {
+                self.open_repo(&owner, &repo, &sha).await;
+            }
+        }
+    }
+
+    /// Shows the user or org in the Repos block
+    async fn open_user(&mut self, owner: &String) -> bool {
+        self.search_user = owner.clone();
+        self.search_repo.clear();
+        self.search().await;
+        if self.searched_user.is_none() {
+            return false;
+        }
+        self.layout.focus_block(BlockType::Repos);
+        return true;
+    }
+
+    /// Shows the commits of the repo, selecting the commit when the sha is not empty
+    async fn open_repo(&mut self, owner: &String, repo: &String, sha: &String) -> bool {
+        if !self.open_user(owner).await {
+            return false;
+        }
+        let selected = match &mut self.searched_user {
+            Some(su) => su.select_repo(&repo),
+            None => false,
+        };
+        if !selected {
+            self.set_status(format!("No repo found with '{}/{}'", owner, repo));
+            return false;
+        }
+        self.handle_repo_select().await;
+        self.layout.focus_block(BlockType::Commits);
+        if !sha.is_empty() {
+            if let Some(su) = &mut self.searched_user {
+                if !su.select_commit(&sha) {
+                    self.status_text = format!("Commit {} is not in the fetched history", sha);
+                }
+            }
+        }
+        return true;
+    }
+
+    fn unread_count(&self) -> usize {
+        return self.notifications.iter().filter(|n| n.unread).count();
+    }
+
+    async fn fetch_notifications(&mut self) {
+        let mut notifications = crate::api::fetch_notifications(&self.user).await;
+        // Newest first in each repo, the repo of the newest notification first
+        let mut repos: Vec<String> = Vec::new();
+        for n in notifications.iter() {
+            if !repos.contains(&n.repo) {
+                repos.push(n.repo.clone());
+            }
+        }
+        notifications.sort_by_key(|n| repos.iter().position(|r| *r == n.repo));
+        self.notifications_list = StateL::new(notifications.len());
+        self.notifications_list.first();
+        self.notifications = notifications;
+    }
+
+    fn selected_notification(&self) -> Option<crate::git::Notification> {
+        let index = self.notifications_list.get_selected_index()?;
+        return self.notifications.get(index).cloned();
+    }
+
+    async fn handle_keys_notifications(&mut self, key: KeyEvent) {
+        let action = match self.keymap.handle(&key) {
+            KeyResult::Action(action) => action,
+            _ => return,
+        };
+        match action {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                self.notifications_list.move_by(action)
+            }
+            Action::Open => self.open_notification().await,
+            Action::Refresh => {
+                self.fetch_notifications().await;
+                self.set_status(format!("{} unread notifications", self.unread_count()));
+            }
+            Action::OpenBrowser => {
+                if let Some(n) = self.selected_notification() {
+                    let url = n.html_url();
+                    if let Err((_, e)) = crate::command::open_url(&url) {
+                        self.set_status(format!("Failed to open {}: {}", url, e));
+                    }
+                }
+            }
+            Action::MarkRead | Action::MarkDone | Action::Unsubscribe => {
+                self.update_notification(action).await
+            }
+            Action::Back | Action::Quit | Action::Notifications => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    /// Marks the selected notification read or done, or unsubscribes from its thread
+    async fn update_notification(&mut self, action: Action) {
+        let (index, n) = match self.notifications_list.get_selected_index() {
+            Some(i) => match self.notifications.get(i) {
+                Some(n) => (i, n.clone()),
+                None => return,
+            },
+            None => return,
+        };
+        let result = match action {
+            Action::MarkRead => crate::api::mark_notification_read(&self.user, &n.id).await,
+            Action::MarkDone => crate::api::mark_notification_done(&self.user, &n.id).await,
+            _ => crate::api::unsubscribe_notification(&self.user, &n.id).await,
+        };
+        if let Err(e) = result {
+            return self.set_status(format!("Failed to update {}: {}", n.title, e));
+        }
+        match action {
+            Action::MarkRead => {
+                self.notifications[index].unread = false;
+                self.set_status(format!("Marked {} read", n.title));
+            }
+            Action::MarkDone => {
+                self.notifications.remove(index);
+                self.notifications_list.new_size(self.notifications.len());
+                self.set_status(format!("Marked {} done", n.title));
+            }
+            _ => self.set_status(format!("Unsubscribed from {}", n.title)),
+        }
+    }
+
+    /// Events received by the logged in user, or the public events of the shown user
+    async fn fetch_events(&mut self) {
+        let login = self.shown_git_user().username.clone();
+        let received = !self.show_su_data();
+        self.events = crate::api::fetch_events(&self.user, &login, received).await;
+        self.events_list = StateL::new(self.events.len());
+        self.events_list.first();
+        self.events_user = login;
+    }
+
+    async fn open_activity(&mut self) {
+        if self.events_user != self.shown_git_user().username {
+            self.fetch_events().await;
+        }
+        self.mode = Mode::Activity;
+    }
+
+    fn selected_event(&self) -> Option<crate::git::Event> {
+        let index = self.events_list.get_selected_index()?;
+        return self.events.get(index).cloned();
+    }
+
+    async fn handle_keys_activity(&mut self, key: KeyEvent) {
+        let action = match self.keymap.handle(&key) {
+            KeyResult::Action(action) => action,
+            _ => return,
+        };
+        match action {
+            Action::MoveUp | Action::MoveDown | Action::MoveTop | Action::MoveBottom => {
+                self.events_list.move_by(action)
+            }
+            Action::Open => self.open_event().await,
+            Action::Refresh => {
+                self.fetch_events().await;
+                self.set_status(format!("Fetched {} events", self.events.len()));
+            }
+            Action::OpenBrowser => {
+                if let Some(event) = self.selected_event() {
+                    let url = match event.sha.is_empty() {
+                        true => format!("https://github.com/{}", event.repo),
+                        false => format!("https://github.com/{}/commit/{}", event.repo, event.sha),
+                    };
+                    if let Err((_, e)) = crate::command::open_url(&url) {
+                        self.set_status(format!("Failed to open {}: {}", url, e));
+                    }
+                }
+            }
+            Action::Back | Action::Quit | Action::Activity => self.mode = Mode::Tui,
+            _ => {}
+        }
+    }
+
+    /// Shows the repo of the selected event, with the pushed commit or the pull request
+    async fn open_event(&mut self) {
+        let event = match self.selected_event() {
+            Some(event) => event,
+            None => return,
+        };
+        let (owner, repo) = match event.repo.split_once('/') {
+            Some((owner, repo)) => (owner.to_string(), repo.to_string()),
+            None => return,
+        };
+        self.mode = Mode::Tui;
+        self.record_view();
+        if !self.open_repo(&owner, &repo, &event.sha).await {
+            return;
+        }
+        self.record_view();
+        if let Some((base, head)) = event.compare {
+            self.compare(base, head).await;
+        }
+    }
+
+    /// Shows the subject of the selected notification, a pull request in the compare view
+    async fn open_notification(&mut self) {
+        let n = match self.selected_notification() {
+            Some(n) => n,
+            None => return,
+        };
+        if n.unread
+            && crate::api::mark_notification_read(&self.user, &n.id)
+                .await
+                .is_ok()
+        {
+            if let Some(i) = self.notifications_list.get_selected_index() {
+                self.notifications[i].unread = false;
+            }
+        }
+        self.mode = Mode::Tui;
+        let (owner, repo) = match n.repo.split_once('/') {
+            Some((owner, repo)) => (owner.to_string(), repo.to_string()),
+            None => return,
+        };
+        let sha = match n.kind.as_str() {
+            "Commit" => n.subject_id(),
+            _ => String::new(),
+        };
+        self.record_view();
+        if !self.open_repo(&owner, &repo, &sha).await {
+            return;
+        }
+        self.record_view();
+        match n.kind.as_str() {
+            "Commit" => {}
+            "PullRequest" => match crate::api::fetch_pull_refs(&self.user, &n.url).await {
+                Some((base, head)) => self.compare(base, head).await,
+                None => self.set_status(format!("Failed to fetch pull request {}", n.title)),
+            },
+            kind => self.set_status(format!(
+                "{} has no view in gierm, {} in the inbox opens it in the browser",
+                kind,
+                self.keymap.keys_for(Action::OpenBrowser)
+            )),
+        }
+    }
+
     async fn search(&mut self) {
+        if self.search_user.trim().is_empty() {
+            self.repo_list.set_filter(self.search_repo.clone());
+            self.show_user_data = true;
+            self.layout.unselect_layout();
+            return;
+        }
         if let Some(su) = &mut self.searched_user {
             if self.search_user.to_lowercase() == su.user.username.to_lowercase() {
                 su.repo_list.set_filter(self.search_repo.clone());
@@ -536,19 +3171,24 @@ impl Tui {
         }
 
         match crate::api::search_gituser(&self.user, &self.search_user).await {
-            Some(mut user) => {
-                user.repos = crate::api::fetch_repos(&self.user, &self.search_user).await;
-                let found = SearchedUser::new(user, self.search_repo.clone());
+            Some(user) => {
+                let found = SearchedUser::new(
+                    user,
+                    self.search_repo.clone(),
+                    self.repo_sort,
+                    self.repo_group,
+                );
                 self.set_status(format!(
-                    "Found user {} with {} repos",
+                    "Found {} {} with {} repos",
+                    if found.user.is_org { "org" } else { "user" },
                     self.search_user,
                     found.user.repos.len()
                 ));
-                self.searched_user = Some(found);
+                self.replace_searched_user(Some(found));
             }
             None => {
                 self.set_status(format!("No user found with '{}'", self.search_user));
-                self.searched_user = None;
+                self.replace_searched_user(None);
             }
         }
 
@@ -576,7 +3216,7 @@ impl Tui {
                         true,
                     )
                 } else {
-                    (repo.commits.len(), false)
+                    (repo.visible_commits().len(), false)
                 };
                 su.commit_list.items_len = commits_len;
                 su.commit_list.state = ListState::default();
@@ -586,8 +3226,8 @@ impl Tui {
                 }
             }
         } else {
-            if self.repo_list_state.state == ListState::default() {
-                self.repo_list_state.next();
+            if self.repo_list.state.state == ListState::default() {
+                self.repo_list.state.next();
                 return;
             }
             if let Some(repo) = self.selected_repo() {
@@ -596,7 +3236,7 @@ impl Tui {
                     self.set_status(format!("Fetched {} commits", len));
                     len
                 } else {
-                    repo.commits.len()
+                    repo.visible_commits().len()
                 };
                 self.commit_list.items_len = commits_len;
                 self.commit_list.state = ListState::default();
@@ -635,8 +3275,9 @@ impl Tui {
     }
 
     async fn handle_enter(&mut self) {
+        self.record_view();
         match self.layout.active_block().block_type() {
-            BlockType::Profile => {}
+            BlockType::Profile => self.handle_profile_select().await,
             BlockType::Repos => self.handle_repo_select().await,
             BlockType::Search => {
                 self.layout.select_layout();
@@ -648,133 +3289,201 @@ impl Tui {
                 }
                 _ => self.mode = Mode::Input,
             },
-            BlockType::Info => {}
-            BlockType::Commits => self.handle_commit_select().await,
-            BlockType::CommitInfo => {}
-            _ => {}
-        }
-    }
-
-    fn draw(&mut self, frame: &mut Frame) {
-        let block_highlight_style = Style::new().green();
-        let status_area_height = if self.status_text.is_empty() { 2 } else { 3 };
-
-        let vertical = Layout::vertical([Min(0)]);
-        let [main_area] = vertical.areas(frame.area());
-        let horizontal = Layout::horizontal([Fill(1), Fill(2)]);
-        let [left_area, right_area] = horizontal.areas(main_area);
-
-        let left_vertical =
-            Layout::vertical([Length(6), Min(0), Length(8), Length(status_area_height)]);
-        let [profile_area, repo_list_area, search_area, status_area] =
-            left_vertical.areas(left_area);
-
-        let right_vertical = Layout::vertical([Length(10), Min(10), Min(10)]);
-        let [info_area, commit_list_area, commit_info_area] = right_vertical.areas(right_area);
-
-        let (username, name, email, bio) = if self.show_su_data() {
-            let su = self
-                .searched_user
-                .as_ref()
-                .expect("Failed to get searched user");
-            (
-                su.user.username.clone(),
-                su.user.name.clone(),
-                su.user.email.clone(),
-                su.user.bio.clone(),
-            )
-        } else {
-            (
-                self.user.git.username.clone(),
-                self.user.git.name.clone(),
-                self.user.git.email.clone(),
-                self.user.git.bio.clone(),
-            )
-        };
+            BlockType::SearchGlobal => match self.mode {
+                Mode::Input => self.global_search().await,
+                _ => self.mode = Mode::Input,
+            },
+            BlockType::Info => {}
+            BlockType::Commits => self.handle_commit_select().await,
+            BlockType::CommitInfo => {
+                let file = self.file_list.get_selected_index().and_then(|i| {
+                    let info = self.selected_commit()?.info.as_ref()?;
+                    let file = info.files.get(i)?;
+                    Some((file.name.clone(), file.patch_diff.clone()))
+                });
+                if let Some((name, patch)) = file {
+                    self.open_diff(name, patch);
+                }
+            }
+            BlockType::Actions => self.handle_actions_select().await,
+            BlockType::Releases => self.open_release(),
+            _ => {}
+        }
+        self.record_view();
+    }
 
-        let profile_block = Block::bordered()
-            .title(username)
-            .border_type(BorderType::Rounded)
-            .border_style(
-                if self.layout.active_block().block_type() == BlockType::Profile {
-                    block_highlight_style
-                } else {
-                    Style::default()
-                },
-            );
-        frame.render_widget(&profile_block, profile_area);
+    fn draw(&mut self, frame: &mut Frame) {
+        let theme = self.theme.clone();
+        let block_highlight_style = theme.border_focused;
+        let status_area_height = if self.status_text.is_empty() { 2 } else { 3 };
+        let [tab_bar_area, main_area] = Layout::vertical([Length(1), Min(0)]).areas(frame.area());
+        self.draw_tab_bar(frame, tab_bar_area);
 
+        let git_user = self.shown_git_user();
         let mut lines = vec![];
         lines.push(Line::from(vec![
             Span::styled("Name: ", Style::default()),
-            Span::styled(name, Style::default()),
+            Span::styled(git_user.name.clone(), Style::default()),
         ]));
         lines.push(Line::from(vec![
             Span::styled("Email: ", Style::default()),
-            Span::styled(email, Style::default()),
+            Span::styled(git_user.email.clone(), Style::default()),
         ]));
         lines.push(Line::from(vec![
             Span::styled("Bio: ", Style::default()),
-            Span::styled(bio, Style::default()),
+            Span::styled(git_user.bio.clone(), Style::default()),
+        ]));
+        let links_title = if git_user.is_org { "Members: " } else { "Orgs: " };
+        let mut links_line = vec![Span::styled(links_title, Style::default())];
+        for (i, link) in self.profile_links().into_iter().enumerate() {
+            if i > 0 {
+                links_line.push(Span::styled(", ", Style::default()));
+            }
+            links_line.push(Span::styled(
+                link,
+                if self.profile_list.get_selected_index() == Some(i) {
+                    theme.selection
+                } else {
+                    Style::default()
+                },
+            ));
+        }
+        lines.push(Line::from(links_line));
+        if git_user.is_org {
+            lines.push(Line::from(vec![
+                Span::styled("Teams: ", Style::default()),
+                Span::styled(git_user.teams.join(", "), Style::default()),
+            ]));
+        }
+        lines.push(Line::from(vec![
+            Span::styled("Followers: ", Style::default()),
+            Span::styled(git_user.followers.to_string(), theme.count),
+            Span::styled("  Following: ", Style::default()),
+            Span::styled(git_user.following.to_string(), theme.count),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Joined: ", Style::default()),
+            Span::styled(
+                git_user.created_at.format("%Y-%m-%d").to_string(),
+                Style::default(),
+            ),
         ]));
         lines.push(Line::from(vec![
             Span::styled("Rate remaining: ", Style::default()),
             Span::styled(self.user.rate().to_string(), Style::default()),
         ]));
+        let contribution_days = match git_user.is_org {
+            true => None,
+            false => Some(git_user.contribution_days()),
+        };
+        let mut profile_height = lines.len() as u16 + 2;
+        if contribution_days.is_some() {
+            profile_height += crate::heatmap::HEATMAP_HEIGHT + 1;
+        }
+
+        let areas = self
+            .layout
+            .areas(main_area, &|block_type| match block_type {
+                BlockType::Profile => profile_height,
+                BlockType::Status => status_area_height,
+                _ => 0,
+            });
+        let area_of = |block_type: BlockType| {
+            areas
+                .iter()
+                .find(|(b, _)| *b == block_type)
+                .map(|(_, area)| *area)
+                .unwrap_or_default()
+        };
+        let profile_area = area_of(BlockType::Profile);
+        let repo_list_area = area_of(BlockType::Repos);
+        let search_area = area_of(BlockType::Search);
+        let status_area = area_of(BlockType::Status);
+        let info_area = area_of(BlockType::Info);
+        let commit_list_area = area_of(BlockType::Commits);
+        let commit_info_area = area_of(BlockType::CommitInfo);
+        let actions_area = area_of(BlockType::Actions);
+        let releases_area = area_of(BlockType::Releases);
+        self.block_areas = areas.clone();
+        self.list_hits.clear();
+
+        let profile_block = Block::bordered()
+            .title(self.shown_git_user().username.clone())
+            .border_type(BorderType::Rounded)
+            .border_style(
+                if self.layout.active_block().block_type() == BlockType::Profile {
+                    block_highlight_style
+                } else {
+                    theme.border
+                },
+            );
+        frame.render_widget(&profile_block, profile_area);
+
+        if let Some(days) = contribution_days {
+            lines.push(Line::from(""));
+            lines.extend(crate::heatmap::heatmap_lines(
+                &days,
+                chrono::Utc::now().date_naive(),
+                profile_block.inner(profile_area).width,
+                &theme,
+            ));
+        }
         let text = Text::from(lines);
         let p = Paragraph::new(text);
         frame.render_widget(p, profile_block.inner(profile_area));
 
-        let repo_list = match self.show_su_data() {
-            true => self
-                .searched_user
-                .as_mut()
-                .expect("Failed to get searched user")
-                .repo_list
-                .get_filtered(),
-            false => self.repo_list.clone(),
-        };
-
-        let mut repo_list_state = match self.show_su_data() {
-            true => self
-                .searched_user
-                .as_mut()
-                .expect("Failed to get searched user")
-                .repo_list
-                .state
-                .state
-                .clone(),
-            false => self.repo_list_state.state.clone(),
+        let (repo_list, selected_row, repos_len, selected_repo) = {
+            let (repo_filter_list, repos) = match self.show_su_data() {
+                true => {
+                    let su = self
+                        .searched_user
+                        .as_mut()
+                        .expect("Failed to get searched user");
+                    (&mut su.repo_list, &su.user.repos)
+                }
+                false => (&mut self.repo_list, &self.user.git.repos),
+            };
+            let matches = repo_filter_list.get_matches();
+            let selected = repo_filter_list.get_index();
+            let (items, selected_row) = crate::filterlist::list_items(
+                &matches,
+                selected,
+                theme.matched,
+                theme.group_header,
+                false,
+                |name| repo_indicators(repos, name, theme.dim),
+            );
+            self.list_hits.push(ListHit::new(
+                BlockType::Repos,
+                Rect::default(),
+                0,
+                crate::filterlist::item_rows(&matches, false),
+            ));
+            (items, selected_row, matches.len(), selected.unwrap_or(0))
         };
+        let mut repo_list_scrollbar_state = ScrollbarState::new(repos_len).position(selected_repo);
 
-        let mut repo_list_scrollbar_state = match self.show_su_data() {
-            true => {
-                let su = self
-                    .searched_user
-                    .as_ref()
-                    .expect("Failed to get searched user");
-                ScrollbarState::new(su.repo_list.state.items_len)
-                    .position(su.repo_list.get_index().unwrap_or(0))
+        let repo_list_title = match &self.searched_user {
+            Some(su) if self.show_su_data() && su.user.is_org => {
+                format!("Repos ({})", su.repo_filter.to_string())
             }
-            false => ScrollbarState::new(self.repo_list_state.items_len)
-                .position(self.repo_list_state.state.selected().unwrap_or(0)),
+            _ => "Repos".to_string(),
         };
-
         let repo_list_block = List::new(repo_list)
             .block(
                 Block::bordered()
-                    .title("Repos")
+                    .title(repo_list_title)
                     .border_type(BorderType::Rounded)
                     .border_style(
                         if self.layout.active_block().block_type() == BlockType::Repos {
                             block_highlight_style
                         } else {
-                            Style::default()
+                            theme.border
                         },
                     ),
             )
-            .style(Style::new().white())
-            .highlight_style(Style::new().italic().blue())
+            .style(theme.text)
+            .highlight_style(theme.selection)
             .highlight_symbol("")
             .repeat_highlight_symbol(true)
             .direction(ListDirection::TopToBottom);
@@ -787,7 +3496,13 @@ impl Tui {
             horizontal: 0,
         };
 
-        frame.render_stateful_widget(&repo_list_block, repo_list_area, &mut repo_list_state);
+        let repo_list_state = self.repo_filter_list().view_state(selected_row);
+        frame.render_stateful_widget(&repo_list_block, repo_list_area, repo_list_state);
+        let repo_list_offset = repo_list_state.offset();
+        if let Some(hit) = self.list_hits.last_mut() {
+            hit.area = repo_list_area.inner(Margin::new(1, 1));
+            hit.offset = repo_list_offset;
+        }
 
         frame.render_stateful_widget(
             scrollbar.clone(),
@@ -802,7 +3517,7 @@ impl Tui {
                 if self.layout.active_block().block_type() == BlockType::Search {
                     block_highlight_style
                 } else {
-                    Style::default()
+                    theme.border
                 },
             );
         let user_search_block = Block::bordered()
@@ -811,11 +3526,11 @@ impl Tui {
             .border_style(
                 if self.layout.active_block().block_type() == BlockType::SearchUser {
                     match self.mode {
-                        Mode::Input => Style::new().blue(),
+                        Mode::Input => theme.border_input,
                         _ => block_highlight_style,
                     }
                 } else {
-                    Style::default()
+                    theme.border
                 },
             );
         let repo_search_block = Block::bordered()
@@ -824,20 +3539,45 @@ impl Tui {
             .border_style(
                 if self.layout.active_block().block_type() == BlockType::SearchRepo {
                     match self.mode {
-                        Mode::Input => Style::new().blue(),
+                        Mode::Input => theme.border_input,
                         _ => block_highlight_style,
                     }
                 } else {
-                    Style::default()
+                    theme.border
+                },
+            );
+
+        let global_search_block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(format!("Global ({})", self.search_kind.to_string()))
+            .border_style(
+                if self.layout.active_block().block_type() == BlockType::SearchGlobal {
+                    match self.mode {
+                        Mode::Input => theme.border_input,
+                        _ => block_highlight_style,
+                    }
+                } else {
+                    theme.border
                 },
             );
 
-        let [user_search_area, repo_search_area] =
-            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)])
+        let [user_search_area, repo_search_area, global_search_area] =
+            Layout::vertical([Length(3), Length(3), Length(3)])
                 .areas(search_block.inner(search_area));
         frame.render_widget(&search_block, search_area);
+        self.block_areas
+            .push((BlockType::SearchUser, user_search_area));
+        self.block_areas
+            .push((BlockType::SearchRepo, repo_search_area));
+        self.block_areas
+            .push((BlockType::SearchGlobal, global_search_area));
         frame.render_widget(&user_search_block, user_search_area);
         frame.render_widget(&repo_search_block, repo_search_area);
+        frame.render_widget(&global_search_block, global_search_area);
+        frame.render_widget(
+            Paragraph::new(self.search_global.clone()).block(Block::default()),
+            global_search_block.inner(global_search_area),
+        );
         frame.render_widget(
             Paragraph::new(self.search_user.clone()).block(Block::default()),
             user_search_block.inner(user_search_area),
@@ -847,22 +3587,32 @@ impl Tui {
             repo_search_block.inner(repo_search_area),
         );
 
+        let status_title = match self.unread_count() {
+            0 => "Status".to_string(),
+            n => format!("Status [{} unread]", n),
+        };
         let status_block = Block::bordered()
-            .title("Status")
-            .border_type(BorderType::Rounded);
+            .title(status_title)
+            .title(Line::from(self.keymap.pending()).right_aligned())
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border);
         frame.render_widget(&status_block, status_area);
         frame.render_widget(
-            Paragraph::new(self.status_text.clone()).block(Block::default()),
+            Paragraph::new(self.status_text.clone())
+                .style(theme.status)
+                .block(Block::default()),
             status_block.inner(status_area),
         );
 
         let mut info_lines: Vec<Line<'_>> = vec![];
         let mut commit_info_lines: Vec<Line<'_>> = vec![];
+        let mut commit_files: Vec<Line> = vec![];
         let repo_name: Option<String>;
         let repo: &crate::git::Repo;
-        let mut commit_list_items: Vec<String> = vec![];
+        let mut commit_list_items: Vec<Line> = vec![];
         let mut commit_list_scrollbar_state: ScrollbarState = ScrollbarState::default();
         let mut commit_list_state = ListState::default();
+        let mut commits_title = "Commits".to_string();
 
         // TODO: refactor more
         repo_name = self.selected_repo_name();
@@ -870,56 +3620,107 @@ impl Tui {
             if self.show_su_data() {
                 let su = self.searched_user.as_ref().expect("Failed to get su");
                 repo = su.user.repos.get(&r_name).unwrap();
-                commit_list_items = repo.commits.iter().map(|c| c.to_string()).collect();
+                commit_list_items =
+                    commit_list_lines(repo, marked_sha_in(&self.marked_commit, repo), &self.theme);
                 commit_list_state = su.commit_list.state.clone();
                 commit_list_scrollbar_state = ScrollbarState::new(su.commit_list.items_len)
                     .position(self.commit_list.get_selected_index().unwrap_or(0));
 
                 let commit_i = su.commit_list.get_selected_index();
                 if let Some(index) = commit_i {
-                    let commit = repo.commits.get(index).map(|c| c);
+                    let commit = repo
+                        .visible_commit_index(index)
+                        .and_then(|i| repo.commits.get(i));
                     commit_info_lines = match commit {
-                        Some(c) => commit_info_text(c),
+                        Some(c) => commit_info_text(c, &self.theme),
                         _ => Vec::default(),
-                    }
+                    };
+                    commit_files = commit
+                        .map(|c| commit_file_items(c, &self.theme))
+                        .unwrap_or_default();
                 }
             } else {
                 repo = self.user.git.repos.get(&r_name).expect("No repo with name");
-                commit_list_items = repo.commits.iter().map(|c| c.to_string()).collect();
+                commit_list_items =
+                    commit_list_lines(repo, marked_sha_in(&self.marked_commit, repo), &self.theme);
                 commit_list_state = self.commit_list.state.clone();
                 commit_list_scrollbar_state = ScrollbarState::new(self.commit_list.items_len)
                     .position(self.commit_list.get_selected_index().unwrap_or(0));
 
                 let commit_i = self.commit_list.get_selected_index();
                 if let Some(index) = commit_i {
-                    let commit = repo.commits.get(index).map(|c| c);
+                    let commit = repo
+                        .visible_commit_index(index)
+                        .and_then(|i| repo.commits.get(i));
                     commit_info_lines = match commit {
-                        Some(c) => commit_info_text(c),
+                        Some(c) => commit_info_text(c, &self.theme),
                         _ => Vec::default(),
-                    }
+                    };
+                    commit_files = commit
+                        .map(|c| commit_file_items(c, &self.theme))
+                        .unwrap_or_default();
                 }
             }
 
-            info_lines.push(Line::from(vec![Span::styled(
-                repo.name.clone(),
-                Style::default(),
-            )]));
+            info_lines.push(Line::from(vec![
+                Span::styled(repo.name.clone(), Style::default()),
+                Span::styled(format!("  {}", repo.visibility), theme.dim),
+                Span::styled(format!("  {}", repo.default_branch), theme.key),
+            ]));
             info_lines.push(Line::from(vec![
                 Span::styled("Description: ", Style::default()),
                 Span::styled(repo.description.clone(), Style::default()),
             ]));
+            let language_lines = crate::langbar::language_lines(
+                &repo.languages,
+                info_area.width.saturating_sub(2),
+                &theme,
+            );
+            if language_lines.is_empty() {
+                info_lines.push(Line::from(vec![
+                    Span::styled("Language: ", Style::default()),
+                    Span::styled(repo.language_name(), Style::default()),
+                ]));
+            }
+            info_lines.extend(language_lines);
+            let stat = |label: &str, value: String| {
+                return vec![
+                    Span::styled(format!("{}: ", label), Style::default()),
+                    Span::styled(value, theme.count),
+                    Span::styled("  ", Style::default()),
+                ];
+            };
+            info_lines.push(Line::from(
+                [
+                    stat("Stars", repo.stars.to_string()),
+                    stat("Forks", repo.forks.to_string()),
+                    stat("Watchers", repo.watchers.to_string()),
+                    stat("Issues", repo.open_issues.to_string()),
+                    stat("Size", repo.size_string()),
+                ]
+                .concat(),
+            ));
             info_lines.push(Line::from(vec![
-                Span::styled("Language: ", Style::default()),
-                Span::styled(repo.language.clone(), Style::default()),
+                Span::styled("License: ", Style::default()),
+                Span::styled(
+                    match repo.license.as_str() {
+                        "" => "None".to_string(),
+                        l => l.to_string(),
+                    },
+                    Style::default(),
+                ),
+                Span::styled("  Topics: ", Style::default()),
+                Span::styled(repo.topics.join(", "), theme.dim),
             ]));
             info_lines.push(Line::from(vec![
                 Span::styled("Last updated: ", Style::default()),
                 Span::styled(repo.updated_at.clone().to_string(), Style::default()),
-            ]));
-            info_lines.push(Line::from(vec![
-                Span::styled("Commits: ", Style::default()),
+                Span::styled("  Commits: ", Style::default()),
                 Span::styled(repo.commits.len().to_string(), Style::default()),
             ]));
+            if !repo.commit_filter.query.is_empty() {
+                commits_title = format!("Commits [{}]", repo.commit_filter.query);
+            }
         }
 
         let text = Text::from(info_lines);
@@ -931,32 +3732,39 @@ impl Tui {
                     if self.layout.active_block().block_type() == BlockType::Info {
                         block_highlight_style
                     } else {
-                        Style::default()
+                        theme.border
                     },
                 ),
         );
         frame.render_widget(info_block, info_area);
 
+        let commit_list_len = commit_list_items.len();
         let commit_list_block = List::new(commit_list_items)
             .block(
                 Block::bordered()
-                    .title("Commits")
+                    .title(commits_title)
                     .border_type(BorderType::Rounded)
                     .border_style(
                         if self.layout.active_block().block_type() == BlockType::Commits {
                             block_highlight_style
                         } else {
-                            Style::default()
+                            theme.border
                         },
                     ),
             )
-            .style(Style::new().white())
-            .highlight_style(Style::new().italic().blue())
+            .style(theme.text)
+            .highlight_style(theme.selection)
             .highlight_symbol("")
             .repeat_highlight_symbol(true)
             .direction(ListDirection::TopToBottom);
 
         frame.render_stateful_widget(&commit_list_block, commit_list_area, &mut commit_list_state);
+        self.list_hits.push(ListHit::new(
+            BlockType::Commits,
+            commit_list_area.inner(Margin::new(1, 1)),
+            commit_list_state.offset(),
+            (0..commit_list_len).map(Some).collect(),
+        ));
 
         frame.render_stateful_widget(
             scrollbar,
@@ -965,8 +3773,15 @@ impl Tui {
         );
 
         // TODO: add list of the modified files and show patch
+        let commit_info_height = commit_info_lines.len() as u16 + 1;
         let commit_info_block = Paragraph::new(Text::from(commit_info_lines))
             .block(Block::default().padding(Padding::uniform(1)));
+        self.file_list.items_len = commit_files.len();
+        let commit_files_block = List::new(commit_files)
+            .style(theme.text)
+            .highlight_style(theme.selection)
+            .highlight_symbol("")
+            .direction(ListDirection::TopToBottom);
 
         let patch_block = Block::bordered()
             .title("Commit Info")
@@ -975,10 +3790,807 @@ impl Tui {
                 if self.layout.active_block().block_type() == BlockType::CommitInfo {
                     block_highlight_style
                 } else {
-                    Style::default()
+                    theme.border
                 },
             );
+        let [_, commit_files_area] = Layout::vertical([Length(commit_info_height), Min(0)])
+            .areas(patch_block.inner(commit_info_area));
         frame.render_widget(patch_block, commit_info_area);
         frame.render_widget(commit_info_block, commit_info_area);
+        frame.render_stateful_widget(
+            commit_files_block,
+            commit_files_area,
+            &mut self.file_list.state,
+        );
+        self.list_hits.push(ListHit::new(
+            BlockType::CommitInfo,
+            commit_files_area,
+            self.file_list.state.offset(),
+            (0..self.file_list.items_len).map(Some).collect(),
+        ));
+
+        // The commit list scrolled to the selection, kept to restore it from the history
+        *self.commit_state().state.offset_mut() = commit_list_state.offset();
+        self.draw_actions(frame, actions_area);
+        self.draw_releases(frame, releases_area);
+
+        match self.mode {
+            Mode::Results => self.draw_search_results(frame, popup_area(main_area, 70, 70)),
+            Mode::CopyMenu => self.draw_copy_menu(frame, popup_area(main_area, 60, 40)),
+            Mode::Palette => self.draw_palette(frame, popup_area(main_area, 60, 50)),
+            Mode::Help => self.draw_help(frame, popup_area(main_area, 60, 70)),
+            Mode::RefPicker => self.draw_ref_picker(frame, popup_area(main_area, 50, 50)),
+            Mode::Compare => self.draw_compare(frame, popup_area(main_area, 80, 80)),
+            Mode::Diff => self.draw_diff(frame, popup_area(main_area, 90, 90)),
+            Mode::FileHistory => self.draw_file_history(frame, popup_area(main_area, 70, 70)),
+            Mode::Blame => self.draw_blame(frame, popup_area(main_area, 90, 90)),
+            Mode::Log => self.draw_log(frame, popup_area(main_area, 90, 90)),
+            Mode::Notifications => self.draw_notifications(frame, popup_area(main_area, 80, 70)),
+            Mode::Activity => self.draw_activity(frame, popup_area(main_area, 80, 70)),
+            Mode::Release => self.draw_release(frame, popup_area(main_area, 80, 80)),
+            Mode::Prompt => {
+                if self.prompt_return == Mode::Log {
+                    self.draw_log(frame, popup_area(main_area, 90, 90));
+                }
+                self.draw_prompt(frame, popup_area(main_area, 60, 100));
+            }
+            _ => {}
+        }
+    }
+
+    fn draw_palette(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let block = Block::bordered()
+            .title("Commands")
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let inner = block.inner(area);
+        let [input_area, list_area] = Layout::vertical([Length(1), Min(0)]).areas(inner);
+
+        let matches = self.palette.get_matches();
+        let keys: Vec<(String, String)> = self
+            .palette
+            .list
+            .iter()
+            .zip(self.palette_actions.iter())
+            .map(|(label, action)| (label.clone(), self.keymap.keys_for(*action)))
+            .collect();
+        let (items, selected_row) = crate::filterlist::list_items(
+            &matches,
+            self.palette.get_index(),
+            theme.matched,
+            theme.group_header,
+            false,
+            |label| {
+                let binding = keys
+                    .iter()
+                    .find(|(l, _)| l == label)
+                    .map(|(_, k)| k.clone())
+                    .unwrap_or_default();
+                match binding.is_empty() {
+                    true => Vec::new(),
+                    false => vec![Span::styled(format!("  {}", binding), theme.dim)],
+                }
+            },
+        );
+        let list = List::new(items)
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled(": ", theme.prompt),
+            Span::raw(self.palette.filter.clone()),
+        ]));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(input, input_area);
+        frame.render_stateful_widget(list, list_area, self.palette.view_state(selected_row));
+    }
+
+    fn draw_ref_picker(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let title = match &self.ref_base {
+            Some(base) => format!("Compare {} with", base),
+            None => "Compare base".to_string(),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let inner = block.inner(area);
+        let [input_area, list_area] = Layout::vertical([Length(1), Min(0)]).areas(inner);
+        let matches = self.ref_picker.get_matches();
+        let (items, selected_row) = crate::filterlist::list_items(
+            &matches,
+            self.ref_picker.get_index(),
+            theme.matched,
+            theme.group_header,
+            false,
+            |_| Vec::new(),
+        );
+        let list = List::new(items)
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled("> ", theme.prompt),
+            Span::raw(self.ref_picker.filter.clone()),
+        ]));
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(input, input_area);
+        frame.render_stateful_widget(list, list_area, self.ref_picker.view_state(selected_row));
+    }
+
+    fn draw_compare(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let comparison = match &self.comparison {
+            Some(c) => c,
+            None => return,
+        };
+        let block = Block::bordered()
+            .title(format!(
+                "Compare {}...{}",
+                crate::git::short_ref(&comparison.base),
+                crate::git::short_ref(&comparison.head)
+            ))
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let inner = block.inner(area);
+        let info = &comparison.info;
+        let header = vec![
+            Line::from(vec![
+                Span::styled(comparison.status.clone(), theme.key),
+                Span::raw(format!(
+                    ", {} ahead and {} behind",
+                    comparison.ahead_by, comparison.behind_by
+                )),
+            ]),
+            Line::from(vec![
+                Span::raw(format!(
+                    "{} commits, {} files changed, ",
+                    comparison.commits.len(),
+                    info.files.len()
+                )),
+                Span::styled(format!("{} insertions(+)", info.additions), theme.diff_add),
+                Span::raw(", "),
+                Span::styled(format!("{} deletions(-)", info.deletions), theme.diff_del),
+            ]),
+        ];
+        let commits: Vec<Line> = comparison
+            .commits
+            .iter()
+            .rev()
+            .map(|c| Line::styled(c.to_string(), theme.dim))
+            .collect();
+        let files: Vec<Line> = info
+            .files
+            .iter()
+            .map(|f| {
+                Line::from(vec![
+                    Span::styled(format!("+{:<4} ", f.additions), theme.diff_add),
+                    Span::styled(format!("-{:<4} ", f.deletions), theme.diff_del),
+                    Span::raw(f.name.clone()),
+                ])
+            })
+            .collect();
+        let commits_height = (commits.len() as u16).min(inner.height / 3);
+        let [header_area, commits_area, files_area] =
+            Layout::vertical([Length(3), Length(commits_height + 1), Min(0)]).areas(inner);
+        let file_list = List::new(files)
+            .style(theme.text)
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(header), header_area);
+        frame.render_widget(Paragraph::new(commits), commits_area);
+        frame.render_stateful_widget(file_list, files_area, &mut self.compare_files.state);
+    }
+
+    fn draw_actions(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let run_id = self.actions_run;
+        let (lines, title) = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => {
+                let title = match run_id.and_then(|id| repo.runs.iter().find(|r| r.id == id)) {
+                    Some(run) => format!("Actions [#{} {}]", run.number, run.name),
+                    None => "Actions".to_string(),
+                };
+                (action_lines(repo, run_id, &theme), title)
+            }
+            None => (Vec::new(), "Actions".to_string()),
+        };
+        self.actions_list.items_len = lines.len();
+        let rows = lines.len();
+        let list = List::new(lines)
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .border_type(BorderType::Rounded)
+                    .border_style(
+                        if self.layout.active_block().block_type() == BlockType::Actions {
+                            theme.border_focused
+                        } else {
+                            theme.border
+                        },
+                    ),
+            )
+            .style(theme.text)
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        frame.render_stateful_widget(list, area, &mut self.actions_list.state);
+        self.list_hits.push(ListHit::new(
+            BlockType::Actions,
+            area.inner(Margin::new(1, 1)),
+            self.actions_list.state.offset(),
+            (0..rows).map(Some).collect(),
+        ));
+    }
+
+    fn draw_activity(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let items: Vec<Line> = self
+            .events
+            .iter()
+            .map(|e| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(
+                            "{:>9} ",
+                            e.created_at
+                                .map(crate::git::relative_time)
+                                .unwrap_or_default()
+                        ),
+                        theme.dim,
+                    ),
+                    Span::styled(format!("{} ", e.actor), theme.key),
+                    Span::raw(format!("{} ", e.summary)),
+                    Span::styled(e.repo.clone(), theme.count),
+                ])
+            })
+            .collect();
+        let title = match self.events_user == self.user.git.username {
+            true => "Activity".to_string(),
+            false => format!("Activity of {}", self.events_user),
+        };
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .title(title)
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.border_focused),
+            )
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.events_list.state);
+    }
+
+    fn draw_notifications(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let selected = self.notifications_list.get_selected_index();
+        let mut lines: Vec<Line> = Vec::new();
+        let mut selected_row = None;
+        for (i, n) in self.notifications.iter().enumerate() {
+            if i == 0 || self.notifications[i - 1].repo != n.repo {
+                lines.push(Line::styled(n.repo.clone(), theme.group_header));
+            }
+            if selected == Some(i) {
+                selected_row = Some(lines.len());
+            }
+            lines.push(Line::from(vec![
+                match n.unread {
+                    true => Span::styled("● ", theme.key),
+                    false => Span::raw("  "),
+                },
+                Span::styled(format!("{:<17}", n.reason), theme.count),
+                Span::styled(format!("{:<12}", n.kind), theme.dim),
+                Span::styled(
+                    n.title.clone(),
+                    match n.unread {
+                        true => theme.text,
+                        false => theme.dim,
+                    },
+                ),
+                Span::styled(
+                    n.updated_at
+                        .map(|t| format!("  {}", t.format("%Y-%m-%d %H:%M")))
+                        .unwrap_or_default(),
+                    theme.dim,
+                ),
+            ]));
+        }
+        if lines.is_empty() {
+            lines.push(Line::styled("No unread notifications", theme.dim));
+        }
+        let hint = format!(
+            " {} read  {} done  {} unsubscribe  {} browser ",
+            self.keymap.keys_for(Action::MarkRead),
+            self.keymap.keys_for(Action::MarkDone),
+            self.keymap.keys_for(Action::Unsubscribe),
+            self.keymap.keys_for(Action::OpenBrowser)
+        );
+        let list = List::new(lines)
+            .block(
+                Block::bordered()
+                    .title(format!("Notifications [{} unread]", self.unread_count()))
+                    .title_bottom(Line::styled(hint, theme.dim))
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.border_focused),
+            )
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        let mut list_state = ListState::default().with_selected(selected_row);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut list_state);
+    }
+
+    fn draw_releases(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let lines: Vec<Line> = match self
+            .selected_repo_name()
+            .and_then(|name| self.repo_by_name(&name))
+        {
+            Some(repo) => repo
+                .releases
+                .iter()
+                .map(|r| {
+                    let mut spans = vec![
+                        Span::styled(format!("{} ", r.tag), theme.key),
+                        Span::raw(format!("{} ", r.name)),
+                        Span::styled(
+                            r.published_at
+                                .map(|t| t.format("%Y-%m-%d").to_string())
+                                .unwrap_or_default(),
+                            theme.dim,
+                        ),
+                    ];
+                    if r.prerelease {
+                        spans.push(Span::styled(" [pre-release]", theme.count));
+                    }
+                    if r.draft {
+                        spans.push(Span::styled(" [draft]", theme.diff_del));
+                    }
+                    Line::from(spans)
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        self.releases_list.items_len = lines.len();
+        let rows = lines.len();
+        let list = List::new(lines)
+            .block(
+                Block::bordered()
+                    .title("Releases")
+                    .border_type(BorderType::Rounded)
+                    .border_style(
+                        if self.layout.active_block().block_type() == BlockType::Releases {
+                            theme.border_focused
+                        } else {
+                            theme.border
+                        },
+                    ),
+            )
+            .style(theme.text)
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        frame.render_stateful_widget(list, area, &mut self.releases_list.state);
+        self.list_hits.push(ListHit::new(
+            BlockType::Releases,
+            area.inner(Margin::new(1, 1)),
+            self.releases_list.state.offset(),
+            (0..rows).map(Some).collect(),
+        ));
+    }
+
+    fn draw_release(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let release = match self.selected_release() {
+            Some(release) => release,
+            None => return,
+        };
+        let mut flags = vec![Span::styled(release.tag.clone(), theme.key)];
+        if release.prerelease {
+            flags.push(Span::styled(" [pre-release]", theme.count));
+        }
+        if release.draft {
+            flags.push(Span::styled(" [draft]", theme.diff_del));
+        }
+        let header = vec![
+            Line::from(flags),
+            Line::styled(
+                format!(
+                    "{} {}",
+                    release.author,
+                    release
+                        .published_at
+                        .map(|t| format!(
+                            "released {} on {}",
+                            crate::git::relative_time(t),
+                            t.format("%Y-%m-%d")
+                        ))
+                        .unwrap_or("has not released it".to_string())
+                ),
+                theme.dim,
+            ),
+        ];
+        let notes = match release.notes.trim().is_empty() {
+            true => vec![Line::styled("No release notes", theme.dim)],
+            false => markdown_lines(&release.notes, &theme),
+        };
+        let assets: Vec<Line> = release
+            .assets
+            .iter()
+            .map(|a| {
+                Line::from(vec![
+                    Span::raw(format!("{} ", a.name)),
+                    Span::styled(
+                        format!("{} ", crate::git::bytes_string(a.size)),
+                        theme.count,
+                    ),
+                    Span::styled(format!("{} downloads", a.download_count), theme.dim),
+                ])
+            })
+            .collect();
+        let block = Block::bordered()
+            .title(match release.name.is_empty() {
+                true => release.tag.clone(),
+                false => release.name.clone(),
+            })
+            .title_bottom(Line::styled(
+                " Enter downloads the selected asset ",
+                theme.dim,
+            ))
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let inner = block.inner(area);
+        let assets_height = match assets.len() {
+            0 => 0,
+            n => (n as u16 + 1).min(inner.height / 3),
+        };
+        let [header_area, notes_area, assets_area] =
+            Layout::vertical([Length(3), Min(0), Length(assets_height)]).areas(inner);
+        let notes = Paragraph::new(notes)
+            .wrap(ratatui::widgets::Wrap { trim: false })
+            .scroll((self.release_scroll as u16, 0));
+        let asset_list = List::new(assets)
+            .block(Block::default().title(Line::styled("Assets", theme.group_header)))
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(Paragraph::new(header), header_area);
+        frame.render_widget(notes, notes_area);
+        frame.render_stateful_widget(asset_list, assets_area, &mut self.release_assets.state);
+    }
+
+    fn draw_log(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let (title, lines) = match &self.log {
+            Some(log) => log,
+            None => return,
+        };
+        let hint = match self.log_search.is_empty() {
+            true => " / search ".to_string(),
+            false => format!(" / search  n/N next/previous: {} ", self.log_search),
+        };
+        let block = Block::bordered()
+            .title(title.clone())
+            .title_bottom(Line::styled(hint, theme.dim))
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let height = block.inner(area).height as usize;
+        self.log_scroll = self.log_scroll.min(lines.len().saturating_sub(height));
+        let gutter = lines.len().to_string().len();
+        // Only the shown lines are parsed, logs can be long
+        let shown: Vec<Line> = lines
+            .iter()
+            .enumerate()
+            .skip(self.log_scroll)
+            .take(height)
+            .map(|(i, l)| {
+                let current = self.log_match == Some(i);
+                let mut spans = vec![Span::styled(
+                    format!("{:>w$} ", i + 1, w = gutter),
+                    match current {
+                        true => theme.matched,
+                        false => theme.dim,
+                    },
+                )];
+                let line = crate::ansi::ansi_line(l);
+                spans.extend(match current {
+                    true => line.patch_style(theme.selection).spans,
+                    false => line.spans,
+                });
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(shown).block(block), area);
+    }
+
+    fn draw_prompt(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let [area] = Layout::vertical([Length(3)]).flex(Flex::Center).areas(area);
+        let (title, hint, prefix) = match self.prompt {
+            PromptKind::CommitFilter => (
+                "Filter commits",
+                " text author: sha: after: before: | author= since= until= path= ",
+                "/ ",
+            ),
+            PromptKind::Dispatch => ("Run workflow", " ref input=value ... ", "> "),
+            PromptKind::LogSearch => ("Search log", " Enter to search ", "/ "),
+        };
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(Line::styled(hint, theme.dim))
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_input);
+        let input = Paragraph::new(Line::from(vec![
+            Span::styled(prefix, theme.prompt),
+            Span::raw(self.prompt_input.clone()),
+        ]))
+        .block(block);
+        frame.render_widget(Clear, area);
+        frame.render_widget(input, area);
+    }
+
+    fn draw_file_history(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let block = Block::bordered()
+            .title(format!("History of {}", self.file_history_path))
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let items: Vec<Line> = self
+            .file_history
+            .iter()
+            .map(|c| {
+                Line::from(vec![
+                    Span::styled(c.sha_short(), theme.key),
+                    Span::styled(format!(" {} ", c.date.format("%Y-%m-%d")), theme.dim),
+                    Span::styled(format!("{:<12} ", c.committer), theme.dim),
+                    Span::raw(c.message_short()),
+                ])
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.file_history_list.state);
+    }
+
+    fn draw_blame(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let blame = match &self.blame {
+            Some(blame) => blame,
+            None => return,
+        };
+        let selected = self
+            .blame_list
+            .get_selected_index()
+            .and_then(|i| blame.range_at(i))
+            .map(|r| format!(" {} {} ", crate::git::short_ref(&r.sha), r.message))
+            .unwrap_or_default();
+        let block = Block::bordered()
+            .title(format!("Blame {}", blame.path))
+            .title_bottom(selected)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let items: Vec<Line> = blame
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                // The commit is shown on the first line of each range only
+                let gutter = match blame.range_at(i) {
+                    Some(r) if r.start == i + 1 => vec![
+                        Span::styled(crate::git::short_ref(&r.sha), theme.key),
+                        Span::styled(
+                            format!(
+                                " {:<12} {} ",
+                                r.author.chars().take(12).collect::<String>(),
+                                r.date.format("%Y-%m-%d")
+                            ),
+                            theme.dim,
+                        ),
+                    ],
+                    _ => vec![Span::raw(" ".repeat(33))],
+                };
+                let mut spans = gutter;
+                spans.push(Span::styled(format!("{:>5} │ ", i + 1), theme.dim));
+                spans.push(Span::raw(text.clone()));
+                Line::from(spans)
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.blame_list.state);
+    }
+
+    fn draw_diff(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let (name, lines) = match &self.diff {
+            Some((name, patch)) => (name.clone(), diff_lines(patch, &theme)),
+            None => return,
+        };
+        let block = Block::bordered()
+            .title(name)
+            .border_type(BorderType::Rounded)
+            .border_style(theme.border_focused);
+        let height = block.inner(area).height as usize;
+        self.diff_scroll = self.diff_scroll.min(lines.len().saturating_sub(height));
+        let p = Paragraph::new(lines)
+            .block(block)
+            .scroll((self.diff_scroll as u16, 0));
+        frame.render_widget(Clear, area);
+        frame.render_widget(p, area);
+    }
+
+    fn draw_help(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let block_type = self.layout.active_block().block_type();
+        let lines: Vec<Line> = self
+            .keymap
+            .help_lines(&block_type)
+            .into_iter()
+            .map(|(keys, description)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<16}", keys), theme.key),
+                    Span::styled(description, Style::default()),
+                ])
+            })
+            .collect();
+        let help_block = Paragraph::new(lines).block(
+            Block::bordered()
+                .title("Keys")
+                .title_bottom(Line::from("Press any key to close").right_aligned())
+                .border_type(BorderType::Rounded)
+                .border_style(theme.border_focused)
+                .padding(Padding::horizontal(1)),
+        );
+        frame.render_widget(Clear, area);
+        frame.render_widget(help_block, area);
+    }
+
+    fn draw_copy_menu(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let options: Vec<Line> = self
+            .copy_options
+            .iter()
+            .map(|(label, value)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<12}", label), theme.dim),
+                    Span::styled(value.clone(), Style::default()),
+                ])
+            })
+            .collect();
+        let copy_block = List::new(options)
+            .block(
+                Block::bordered()
+                    .title("Copy")
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.border_focused),
+            )
+            .highlight_style(theme.selection)
+            .direction(ListDirection::TopToBottom);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(&copy_block, area, &mut self.copy_list.state);
+    }
+
+    fn draw_search_results(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = self.theme.clone();
+        let results: Vec<String> = self.search_results.iter().map(|r| r.to_string()).collect();
+        let results_block = List::new(results)
+            .block(
+                Block::bordered()
+                    .title(format!(
+                        "Search {}: {}",
+                        self.search_kind.to_string(),
+                        self.search_global
+                    ))
+                    .border_type(BorderType::Rounded)
+                    .border_style(theme.border_focused),
+            )
+            .style(theme.text)
+            .highlight_style(theme.selection)
+            .highlight_symbol("")
+            .direction(ListDirection::TopToBottom);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(&results_block, area, &mut self.search_results_list.state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn test_tui() -> Tui {
+        let mut user = crate::git::User::new("me".to_string(), "".to_string());
+        for r in 0..60 {
+            let mut repo = crate::git::Repo::new(
+                "me".to_string(),
+                format!("repo{:02}", r),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+            );
+            for c in 0..100 {
+                repo.commits.push(crate::git::Commit::new(
+                    format!("commit {}", c),
+                    format!("{:02}{:03}", r, c),
+                    "me".to_string(),
+                    "".to_string(),
+                ));
+            }
+            user.git.repos.insert(repo);
+        }
+        return Tui::new(
+            user,
+            KeyMap::new(&None),
+            crate::theme::Theme::new(&None),
+            TuiLayout::default_layout(),
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+        );
+    }
+
+    /// Selects the far rows first, so that the lists scroll further than the selection needs
+    fn select(
+        tui: &mut Tui,
+        terminal: &mut Terminal<TestBackend>,
+        repo: [usize; 2],
+        commit: [usize; 2],
+    ) {
+        for i in 0..2 {
+            tui.repo_list.state.state.select(Some(repo[i]));
+            tui.commit_list = StateL::new(100);
+            tui.commit_list.state.select(Some(commit[i]));
+            terminal.draw(|frame| tui.draw(frame)).unwrap();
+        }
+    }
+
+    fn offsets(tui: &mut Tui) -> (usize, usize) {
+        return (tui.repo_list.view.offset(), tui.commit_list.state.offset());
+    }
+
+    #[tokio::test]
+    async fn history_restores_scroll_offsets() {
+        let mut tui = test_tui();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+
+        select(&mut tui, &mut terminal, [55, 50], [90, 85]);
+        let first = offsets(&mut tui);
+        assert!(first.0 > 0 && first.1 > 0);
+        tui.record_view();
+
+        select(&mut tui, &mut terminal, [30, 25], [60, 55]);
+        let second = offsets(&mut tui);
+        assert_ne!(first, second);
+        tui.record_view();
+
+        tui.run_action(Action::HistoryBack).await;
+        assert_eq!(tui.selected_repo_name(), Some("repo50".to_string()));
+        assert_eq!(tui.commit_list.get_selected_index(), Some(85));
+        assert_eq!(offsets(&mut tui), first);
+        terminal.draw(|frame| tui.draw(frame)).unwrap();
+        assert_eq!(offsets(&mut tui), first);
+
+        tui.run_action(Action::HistoryForward).await;
+        assert_eq!(tui.selected_repo_name(), Some("repo25".to_string()));
+        assert_eq!(tui.commit_list.get_selected_index(), Some(55));
+        terminal.draw(|frame| tui.draw(frame)).unwrap();
+        assert_eq!(offsets(&mut tui), second);
     }
 }